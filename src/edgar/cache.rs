@@ -0,0 +1,251 @@
+// src/edgar/cache.rs
+use crate::edgar::config::EdgarConfig;
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Kind of EDGAR resource being fetched; decides how long a cached copy stays fresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// `company_tickers_exchange.json` and friends, refreshed daily by SEC
+    TickerDirectory,
+    /// `submissions/CIK##########.json`, changes whenever the company files
+    Submissions,
+    /// Anything under an accession-number folder in the archives; immutable once published
+    FilingDocument,
+}
+
+/// Freshness lifetimes per resource kind. `None` means the entry never expires.
+#[derive(Debug, Clone)]
+pub struct CacheTtls {
+    pub ticker_directory: Option<Duration>,
+    pub submissions: Option<Duration>,
+}
+
+impl CacheTtls {
+    pub fn from_config(config: &EdgarConfig) -> Self {
+        Self {
+            ticker_directory: Some(Duration::from_secs(config.cache_ttl_tickers_secs)),
+            submissions: Some(Duration::from_secs(config.cache_ttl_submissions_secs)),
+        }
+    }
+
+    fn ttl_for(&self, kind: ResourceKind) -> Option<Duration> {
+        match kind {
+            ResourceKind::TickerDirectory => self.ticker_directory,
+            ResourceKind::Submissions => self.submissions,
+            ResourceKind::FilingDocument => None,
+        }
+    }
+}
+
+/// Metadata stored next to each cached body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntryMeta {
+    pub url: String,
+    /// RFC 3339 timestamp of the last successful fetch or revalidation
+    pub fetched_at: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    pub body_len: usize,
+}
+
+/// A cached response loaded from disk.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub meta: CacheEntryMeta,
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    /// Whether the entry is still within its TTL for the given resource kind.
+    pub fn is_fresh(&self, kind: ResourceKind, ttls: &CacheTtls) -> bool {
+        let Some(ttl) = ttls.ttl_for(kind) else { return true };
+        let Ok(fetched_at) = chrono::DateTime::parse_from_rfc3339(&self.meta.fetched_at) else { return false };
+        let age = chrono::Utc::now() - fetched_at.with_timezone(&chrono::Utc);
+        age.to_std().map(|age| age < ttl).unwrap_or(true) // Future timestamp (clock skew): treat as fresh
+    }
+
+    /// Conditional request headers (`If-None-Match` / `If-Modified-Since`) for revalidation.
+    pub fn validators(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = self.meta.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = self.meta.last_modified.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+}
+
+/// Content-addressed on-disk cache of EDGAR HTTP responses.
+///
+/// Entries are keyed by the SHA-256 of the request URL and stored as
+/// `<dir>/<first two hex chars>/<hash>.json` (metadata) plus `<hash>.body` (raw bytes).
+#[derive(Debug)]
+pub struct HttpCache {
+    dir: PathBuf,
+    ttls: CacheTtls,
+}
+
+impl HttpCache {
+    pub fn new<P: AsRef<Path>>(dir: P, ttls: CacheTtls) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        tracing::debug!("Using EDGAR response cache at {}", dir.display());
+        Ok(Self { dir, ttls })
+    }
+
+    pub fn ttls(&self) -> &CacheTtls {
+        &self.ttls
+    }
+
+    /// Hex SHA-256 of the URL, used as the cache key.
+    pub fn key_for(url: &str) -> String {
+        format!("{:x}", Sha256::digest(url.as_bytes()))
+    }
+
+    fn paths_for(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = Self::key_for(url);
+        let shard = self.dir.join(&key[..2]);
+        (shard.join(format!("{}.json", key)), shard.join(format!("{}.body", key)))
+    }
+
+    /// Loads a cached response, if present and readable. Corrupt entries are treated as misses.
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let (meta_path, body_path) = self.paths_for(url);
+        let meta: CacheEntryMeta = match fs::read(&meta_path) {
+            Ok(raw) => serde_json::from_slice(&raw).ok()?,
+            Err(_) => return None,
+        };
+        if meta.url != url {
+            tracing::warn!("Cache key collision for {} (entry belongs to {}), ignoring", url, meta.url);
+            return None;
+        }
+        let body = fs::read(&body_path).ok()?;
+        if body.len() != meta.body_len {
+            tracing::warn!("Cached body for {} is truncated, ignoring", url);
+            return None;
+        }
+        Some(CachedResponse { meta, body })
+    }
+
+    /// Stores a response body and its relevant headers.
+    pub fn put(&self, url: &str, headers: &HeaderMap, body: &[u8]) -> io::Result<()> {
+        let header_value = |name| headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok()).map(str::to_string);
+        let meta = CacheEntryMeta {
+            url: url.to_string(),
+            fetched_at: chrono::Utc::now().to_rfc3339(),
+            etag: header_value(ETAG),
+            last_modified: header_value(LAST_MODIFIED),
+            headers: headers.iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect(),
+            body_len: body.len(),
+        };
+        let (meta_path, body_path) = self.paths_for(url);
+        // Body first, so a crash never leaves metadata pointing at a missing body
+        write_atomic(&body_path, body)?;
+        write_atomic(&meta_path, &serde_json::to_vec_pretty(&meta).map_err(io::Error::other)?)
+    }
+
+    /// Marks an entry as freshly validated (after a `304 Not Modified`).
+    pub fn touch(&self, entry: &CachedResponse) -> io::Result<()> {
+        let mut meta = entry.meta.clone();
+        meta.fetched_at = chrono::Utc::now().to_rfc3339();
+        let (meta_path, _) = self.paths_for(&meta.url);
+        write_atomic(&meta_path, &serde_json::to_vec_pretty(&meta).map_err(io::Error::other)?)
+    }
+}
+
+/// Distinguishes the temporary files of concurrent writers within one process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes to a temporary sibling file and renames it into place. The temporary name is unique
+/// per target file and writer (process and counter), so concurrent writers of the same entry,
+/// whether tasks of one run or runs sharing an output directory, never rename each other's file.
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!("{}.{}.{}.tmp", file_name, std::process::id(),
+                                               TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ttls() -> CacheTtls {
+        CacheTtls { ticker_directory: Some(Duration::from_secs(3600)), submissions: Some(Duration::ZERO) }
+    }
+
+    #[test]
+    fn test_roundtrip_and_freshness() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(dir.path(), ttls()).unwrap();
+        let url = "https://www.sec.gov/Archives/edgar/data/320193/000032019323000106/aapl-20230930.htm";
+
+        assert!(cache.get(url).is_none());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+        headers.insert(LAST_MODIFIED, HeaderValue::from_static("Fri, 03 Nov 2023 10:00:00 GMT"));
+        cache.put(url, &headers, b"<html>10-K</html>").unwrap();
+
+        let entry = cache.get(url).expect("entry should be cached");
+        assert_eq!(entry.body, b"<html>10-K</html>");
+        assert_eq!(entry.meta.etag.as_deref(), Some("\"abc\""));
+        assert!(entry.is_fresh(ResourceKind::FilingDocument, cache.ttls()), "Filing documents never expire");
+        assert!(entry.is_fresh(ResourceKind::TickerDirectory, cache.ttls()));
+        assert!(!entry.is_fresh(ResourceKind::Submissions, cache.ttls()), "Zero TTL is immediately stale");
+
+        let validators = entry.validators();
+        assert_eq!(validators.get(IF_NONE_MATCH).unwrap(), "\"abc\"");
+        assert_eq!(validators.get(IF_MODIFIED_SINCE).unwrap(), "Fri, 03 Nov 2023 10:00:00 GMT");
+    }
+
+    #[test]
+    fn test_truncated_body_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(dir.path(), ttls()).unwrap();
+        let url = "https://data.sec.gov/submissions/CIK0000320193.json";
+        cache.put(url, &HeaderMap::new(), b"{\"cik\":\"320193\"}").unwrap();
+
+        let (_, body_path) = cache.paths_for(url);
+        fs::write(body_path, b"{\"ci").unwrap();
+        assert!(cache.get(url).is_none());
+    }
+
+    #[test]
+    fn test_concurrent_writes_keep_files_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let (meta, body) = (dir.path().join("key.json"), dir.path().join("key.body"));
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        write_atomic(&meta, b"meta").unwrap();
+                        write_atomic(&body, b"body").unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(fs::read(&meta).unwrap(), b"meta");
+        assert_eq!(fs::read(&body).unwrap(), b"body");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2, "no temporary files left behind");
+    }
+}
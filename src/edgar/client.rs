@@ -1,167 +1,861 @@
 // src/edgar/client.rs
 use crate::utils::error::EdgarError;
-use reqwest::header;
+use reqwest::header::{self, HeaderMap};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
 use std::time::Duration;
-use crate::edgar::models::{CompanySubmission, FilingInfo};
-
-// IMPORTANT: Replace with your actual details or make configurable
-const EDGAR_USER_AGENT: &str = "Orot Capital Ross Granowski rgranowski@gmail.com";
-// SEC asks for 10 requests/second max. Be conservative. >100ms delay.
-const EDGAR_REQUEST_DELAY_MS: u64 = 150;
-
-/// Creates a reqwest client configured for EDGAR interaction.
-fn build_edgar_client() -> Result<reqwest::Client, reqwest::Error> {
-    reqwest::Client::builder()
-        .user_agent(EDGAR_USER_AGENT) // Set the required User-Agent
-        // Can add more config like timeouts here
-        .build()
+use tokio::sync::OnceCell;
+use crate::edgar::cache::{CachedResponse, HttpCache, ResourceKind};
+use crate::edgar::config::EdgarConfig;
+use crate::edgar::directory::{CompanyDirectory, CompanyEntry};
+use crate::edgar::fiscal::{filing_year_window, resolve_fiscal_period};
+use crate::edgar::forms::{FormFilter, FormMatch, QUARTERLY_REPORT_FORMS};
+use crate::edgar::models::{normalize_accession_number, CompanySubmission, FilingDocument, FilingInfo, FilingsList, FolderIndex};
+use crate::edgar::offline::FixtureStore;
+use crate::edgar::rate_limit::RateLimiter;
+use crate::edgar::retry::{parse_retry_after, RetryPolicy};
+use crate::edgar::sgml::{parse_full_submission, parse_submission_header, SubmissionDocument, SubmissionHeader};
+
+// Accept header used for filing documents; SEC uses various content types, but often text/html
+const ACCEPT_DOCUMENTS: &str = "application/xml,text/html,text/plain,*/*";
+const ACCEPT_JSON: &str = "application/json";
+
+/// EDGAR HTTP client. Owns a single connection pool and a token-bucket rate limiter.
+///
+/// Cloning is cheap and every clone shares the same pool and limiter, so the SEC
+/// request-rate limit holds across all tasks using the client concurrently.
+#[derive(Clone)]
+pub struct EdgarClient {
+    http: reqwest::Client,
+    limiter: Arc<RateLimiter>,
+    retry: RetryPolicy,
+    cache: Option<Arc<HttpCache>>,
+    /// `Some` in offline mode: never touch the network, replay cache/fixtures only
+    offline: Option<OfflineSource>,
+    /// Ticker directory, downloaded (or read from the cache) on first use
+    directory: Arc<OnceCell<CompanyDirectory>>,
+    /// Every name ever used on EDGAR (`cik-lookup-data.txt`), only loaded for names not in `directory`
+    historical_names: Arc<OnceCell<CompanyDirectory>>,
+    config: Arc<EdgarConfig>,
+}
+
+/// Where offline mode looks for responses (the cache is always consulted first).
+#[derive(Clone)]
+struct OfflineSource {
+    fixtures: Option<FixtureStore>,
+}
+
+/// A successful response with its body fully read.
+struct HttpResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
 }
 
-/// Downloads a specific filing document from its URL.
-/// Includes mandatory User-Agent and basic rate limiting.
-pub async fn download_filing_doc(url: &str) -> Result<String, EdgarError> {
-    let client = build_edgar_client()?; // Propagate client build error if any
-
-    tracing::info!("Downloading document from: {}", url);
-    tracing::debug!("Using User-Agent: {}", EDGAR_USER_AGENT);
-
-    // --- Basic Rate Limiting ---
-    // In a real app, use a more sophisticated approach like `governor`
-    // especially if making concurrent requests.
-    tokio::time::sleep(Duration::from_millis(EDGAR_REQUEST_DELAY_MS)).await;
-    // --------------------------
-
-    let response = client.get(url)
-        // SEC uses various content types, but often text/html for filings
-        .header(header::ACCEPT, "application/xml,text/html,text/plain,*/*")
-        .send()
-        .await?; // Propagates reqwest::Error as EdgarError::Network
-
-    // Check if the request was successful (status code 2xx)
-    let status = response.status();
-    if !status.is_success() {
-         tracing::error!("HTTP error status: {} for URL: {}", status, url);
-         // Check for specific common errors
-         if status == reqwest::StatusCode::FORBIDDEN {
-              tracing::warn!("Received 403 Forbidden - check User-Agent and rate limits.");
-              return Err(EdgarError::RateLimited);
-         }
-         if status == reqwest::StatusCode::NOT_FOUND {
-              tracing::warn!("Received 404 Not Found for URL: {}", url);
-               return Err(EdgarError::FilingDocNotFound(url.to_string()));
-         }
-         // Return generic HTTP error
-         return Err(EdgarError::Http(status));
-    }
-
-    // Read the response body as text
-    let body = response.text().await?; // Propagates reqwest::Error as EdgarError::Network
-    tracing::debug!("Successfully downloaded {} bytes from {}", body.len(), url);
-
-    Ok(body)
+impl HttpResponse {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    fn json<T: DeserializeOwned>(&self) -> Result<T, EdgarError> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| EdgarError::Parse(format!("Invalid JSON response: {}", e)))
+    }
 }
 
-/// Gets the CIK (Central Index Key) for a ticker symbol
-pub async fn get_cik_from_ticker(ticker: &str) -> Result<String, EdgarError> {
-    let ticker = ticker.to_uppercase();
-    let url = "https://www.sec.gov/files/company_tickers.json";
-    
-    let client = build_edgar_client()?;
-    tokio::time::sleep(Duration::from_millis(EDGAR_REQUEST_DELAY_MS)).await;
-    
-    let response = client.get(url)
-        .send()
-        .await?;
-        
-    if !response.status().is_success() {
-        return Err(EdgarError::Http(response.status()));
-    }
-    
-    let json: serde_json::Value = response.json().await?;
-    
-    // Iterate through the company list to find the matching ticker
-    for (_idx, company) in json.as_object().ok_or(EdgarError::Parse("Invalid JSON structure".to_string()))? {
-        if let Some(company_ticker) = company.get("ticker") {
-            if company_ticker.as_str().unwrap_or_default().to_uppercase() == ticker {
-                if let Some(cik) = company.get("cik_str") {
-                    // Format CIK with leading zeros to 10 digits
-                    let cik_num = cik.as_u64().ok_or(EdgarError::Parse("Invalid CIK format".to_string()))?;
-                    return Ok(format!("{:010}", cik_num));
+impl EdgarClient {
+    /// Creates a client configured for EDGAR interaction (User-Agent, timeouts, rate limit).
+    pub fn new(mut config: EdgarConfig) -> Result<Self, EdgarError> {
+        // Base URLs are joined with "/path", so drop trailing slashes from overrides
+        for url in [&mut config.archives_base_url, &mut config.data_base_url, &mut config.files_base_url] {
+            let trimmed_len = url.trim_end_matches('/').len();
+            url.truncate(trimmed_len);
+        }
+        let http = reqwest::Client::builder()
+            .user_agent(config.user_agent_header()) // Set the required User-Agent
+            .timeout(config.timeout())
+            .connect_timeout(config.connect_timeout())
+            .build()?;
+        // Capacity of one token: never burst above the configured per-second rate.
+        let limiter = Arc::new(RateLimiter::new(config.requests_per_second, 1));
+        let retry = RetryPolicy::from_config(&config);
+
+        tracing::debug!("Created EDGAR client (User-Agent: {}, {} req/s)", config.user_agent_header(), config.requests_per_second);
+        Ok(Self { http, limiter, retry, cache: None, offline: None, directory: Arc::default(), historical_names: Arc::default(), config: Arc::new(config) })
+    }
+
+    pub fn config(&self) -> &EdgarConfig {
+        &self.config
+    }
+
+    /// Enables the on-disk response cache for every request made by this client (and its clones).
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Switches the client to offline mode. Requests are answered from the response cache
+    /// (ignoring TTLs) or from `fixtures`, and fail with `EdgarError::OfflineMiss` otherwise.
+    pub fn offline(mut self, fixtures: Option<FixtureStore>) -> Self {
+        self.offline = Some(OfflineSource { fixtures });
+        self
+    }
+
+    /// Serves a request without the network: any cached copy, then a fixture file.
+    fn get_offline(&self, source: &OfflineSource, url: &str) -> Result<HttpResponse, EdgarError> {
+        let body = self.cache.as_deref().and_then(|cache| cache.get(url)).map(|entry| entry.body)
+            .or_else(|| source.fixtures.as_ref().and_then(|fixtures| fixtures.load(url)))
+            .ok_or_else(|| EdgarError::OfflineMiss(url.to_string()))?;
+        tracing::debug!("Offline: serving {} ({} bytes)", url, body.len());
+        Ok(HttpResponse { status: StatusCode::OK, headers: HeaderMap::new(), body })
+    }
+
+    /// Fetches a URL through the response cache (if enabled).
+    ///
+    /// Fresh entries are served from disk; stale entries with an ETag/Last-Modified are
+    /// revalidated with a conditional request, and a `304 Not Modified` reuses the cached body.
+    async fn get(&self, url: &str, accept: &str, kind: ResourceKind) -> Result<HttpResponse, EdgarError> {
+        if let Some(source) = &self.offline {
+            return self.get_offline(source, url);
+        }
+        let Some(cache) = self.cache.as_deref() else {
+            return self.get_with_retry(url, accept, &HeaderMap::new()).await;
+        };
+
+        let cached = cache.get(url);
+        if let Some(entry) = &cached {
+            if entry.is_fresh(kind, cache.ttls()) {
+                tracing::debug!("Cache hit for {}", url);
+                return Ok(HttpResponse { status: StatusCode::OK, headers: HeaderMap::new(), body: entry.body.clone() });
+            }
+        }
+
+        let validators = cached.as_ref().map(CachedResponse::validators).unwrap_or_default();
+        let response = self.get_with_retry(url, accept, &validators).await?;
+
+        match cached {
+            Some(entry) if response.status == StatusCode::NOT_MODIFIED => {
+                tracing::debug!("Cached copy of {} revalidated (304 Not Modified)", url);
+                if let Err(e) = cache.touch(&entry) {
+                    tracing::warn!("Failed to refresh cache entry for {}: {}", url, e);
+                }
+                Ok(HttpResponse { status: StatusCode::OK, headers: response.headers, body: entry.body })
+            }
+            _ => {
+                if let Err(e) = cache.put(url, &response.headers, &response.body) {
+                    tracing::warn!("Failed to cache response for {}: {}", url, e);
                 }
+                Ok(response)
             }
         }
     }
-    
-    Err(EdgarError::Parse(format!("Could not find CIK for ticker {}", ticker)))
-}
 
-/// Fetches the company submission data for a given CIK
-pub async fn get_company_submissions(cik: &str) -> Result<CompanySubmission, EdgarError> {
-    let url = format!("https://data.sec.gov/submissions/CIK{}.json", cik);
-    
-    let client = build_edgar_client()?;
-    tokio::time::sleep(Duration::from_millis(EDGAR_REQUEST_DELAY_MS)).await;
-    
-    let response = client.get(&url)
-        .send()
-        .await?;
-        
-    if !response.status().is_success() {
-        return Err(EdgarError::Http(response.status()));
-    }
-    
-    let submission: CompanySubmission = response.json().await?;
-    Ok(submission)
-}
+    /// Sends a rate-limited GET request, retrying transient failures per the `RetryPolicy`.
+    /// The body is read inside the retry loop so a connection reset mid-download is retried too.
+    async fn get_with_retry(&self, url: &str, accept: &str, validators: &HeaderMap) -> Result<HttpResponse, EdgarError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.get_once(url, accept, validators).await {
+                Ok(response) => {
+                    if attempt > 1 {
+                        tracing::info!("Request to {} succeeded on attempt {}", url, attempt);
+                    }
+                    return Ok(response);
+                }
+                Err((error, retry_after)) => {
+                    if !self.retry.should_retry(attempt, &error) {
+                        // Fatal errors are returned as they are, even after earlier transient failures
+                        if attempt == 1 || !error.is_retryable() {
+                            return Err(error);
+                        }
+                        tracing::warn!("Giving up on {} after {} attempts: {}", url, attempt, error);
+                        return Err(EdgarError::RetriesExhausted { attempts: attempt, source: Box::new(error) });
+                    }
+                    let delay = self.retry.delay_for(attempt, retry_after);
+                    tracing::warn!("Attempt {}/{} for {} failed ({}), retrying in {:?}",
+                                   attempt, self.retry.max_attempts, url, error, delay);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Performs a single attempt. On failure also returns any `Retry-After` hint from the server.
+    /// A `304 Not Modified` answer to a conditional request counts as success.
+    async fn get_once(&self, url: &str, accept: &str, validators: &HeaderMap) -> Result<HttpResponse, (EdgarError, Option<Duration>)> {
+        self.limiter.acquire().await;
+
+        let response = self.http.get(url)
+            .header(header::ACCEPT, accept)
+            .headers(validators.clone())
+            .send()
+            .await
+            .map_err(|e| (EdgarError::Network(e), None))?;
+
+        // Check if the request was successful (status code 2xx)
+        let status = response.status();
+        let not_modified = status == StatusCode::NOT_MODIFIED && !validators.is_empty();
+        if !status.is_success() && !not_modified {
+            let retry_after = parse_retry_after(response.headers());
+            tracing::error!("HTTP error status: {} for URL: {}", status, url);
+            // Check for specific common errors
+            if status == StatusCode::FORBIDDEN {
+                tracing::warn!("Received 403 Forbidden - check User-Agent and rate limits.");
+                return Err((EdgarError::RateLimited, retry_after));
+            }
+            if status == StatusCode::NOT_FOUND {
+                tracing::warn!("Received 404 Not Found for URL: {}", url);
+                return Err((EdgarError::FilingDocNotFound(url.to_string()), None));
+            }
+            // Return generic HTTP error
+            return Err((EdgarError::Http(status), retry_after));
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await.map_err(|e| (EdgarError::Network(e), None))?;
+        Ok(HttpResponse { status, headers, body: body.to_vec() })
+    }
+
+    /// Downloads a specific filing document from its URL.
+    pub async fn download_filing_doc(&self, url: &str) -> Result<String, EdgarError> {
+        tracing::info!("Downloading document from: {}", url);
 
-/// Finds 10-K filings for a given ticker within a year range
-pub async fn find_10k_filings(ticker: &str, start_year: Option<u32>, end_year: Option<u32>) 
-    -> Result<Vec<FilingInfo>, EdgarError> 
-{
-    let cik = get_cik_from_ticker(ticker).await?;
-    let submissions = get_company_submissions(&cik).await?;
-    
-    let mut filings = Vec::new();
-    
-    // Process recent filings
-    for i in 0..submissions.filings.recent.accessionNumber.len() {
-        let form = submissions.filings.recent.form.get(i)
-            .ok_or_else(|| EdgarError::Parse("Missing form type".to_string()))?;
-            
-        // Filter for 10-K filings
-        if form == "10-K" {
-            let filing_date = submissions.filings.recent.filingDate.get(i)
-                .ok_or_else(|| EdgarError::Parse("Missing filing date".to_string()))?;
-                
-            // Parse year from filing date (format: YYYY-MM-DD)
-            let year = filing_date[0..4].parse::<u32>()
-                .map_err(|_| EdgarError::Parse("Invalid date format".to_string()))?;
-                
-            // Apply year filtering if specified
-            if (start_year.is_none() || year >= start_year.unwrap()) && 
-               (end_year.is_none() || year <= end_year.unwrap()) {
-                
-                let acc_num = submissions.filings.recent.accessionNumber.get(i)
+        let response = self.get(url, ACCEPT_DOCUMENTS, ResourceKind::FilingDocument).await?;
+
+        // Decode the response body as text (older filings are not always valid UTF-8)
+        let body = response.text();
+        tracing::debug!("Successfully downloaded {} bytes from {}", body.len(), url);
+
+        Ok(body)
+    }
+
+    /// Downloads the full submission text file of a filing and splits it into its documents.
+    pub async fn download_full_submission(&self, filing: &FilingInfo) -> Result<Vec<SubmissionDocument>, EdgarError> {
+        let url = filing.full_submission_url(&self.config.archives_base_url);
+        let documents = parse_full_submission(&self.download_filing_doc(&url).await?)?;
+        tracing::debug!("Full submission {} holds {} documents", url, documents.len());
+        Ok(documents)
+    }
+
+    /// Loads the ticker directory (`company_tickers_exchange.json`) once per client and its clones.
+    pub async fn company_directory(&self) -> Result<&CompanyDirectory, EdgarError> {
+        self.directory.get_or_try_init(|| async {
+            let url = format!("{}/company_tickers_exchange.json", self.config.files_base_url);
+            let response = self.get(&url, ACCEPT_JSON, ResourceKind::TickerDirectory).await?;
+            let directory = CompanyDirectory::from_json(&response.body)?;
+            tracing::debug!("Loaded ticker directory with {} entries", directory.len());
+            Ok(directory)
+        }).await
+    }
+
+    /// Loads `cik-lookup-data.txt`, the names of every EDGAR filer including former names.
+    /// Large (tens of MB), so it is only fetched when a name isn't in the ticker directory.
+    pub async fn historical_names(&self) -> Result<&CompanyDirectory, EdgarError> {
+        self.historical_names.get_or_try_init(|| async {
+            let url = format!("{}/edgar/cik-lookup-data.txt", self.config.archives_base_url);
+            let names = CompanyDirectory::from_cik_lookup(&self.get(&url, ACCEPT_DOCUMENTS, ResourceKind::TickerDirectory).await?.text());
+            tracing::debug!("Loaded {} historical EDGAR names", names.len());
+            Ok(names)
+        }).await
+    }
+
+    /// Resolves a ticker (any share-class notation), a raw CIK or a company name to a directory entry.
+    /// Names not in the ticker directory are looked up among all names ever filed under, which finds
+    /// delisted companies and companies by a former name.
+    pub async fn resolve_company(&self, identifier: &str) -> Result<CompanyEntry, EdgarError> {
+        let directory = self.company_directory().await?;
+        match directory.resolve(identifier) {
+            Err(EdgarError::CompanyNotFound(_)) => {
+                tracing::info!("'{}' is not a listed company, searching historical EDGAR names", identifier);
+                let historical = self.historical_names().await?.resolve(identifier)?;
+                // A renamed company that is still listed keeps its ticker
+                Ok(directory.by_cik(&historical.cik).cloned().unwrap_or(historical))
+            }
+            resolved => resolved,
+        }
+    }
+
+    /// Fetches the company submission data for a given CIK
+    pub async fn get_company_submissions(&self, cik: &str) -> Result<CompanySubmission, EdgarError> {
+        let url = format!("{}/submissions/CIK{}.json", self.config.data_base_url, cik);
+
+        let submission: CompanySubmission = self.get(&url, ACCEPT_JSON, ResourceKind::Submissions).await?.json()?;
+        Ok(submission)
+    }
+
+    /// Fetches one of the older submission pages listed in `filings.files`
+    /// (e.g. `CIK0000320193-submissions-001.json`).
+    pub async fn get_submissions_page(&self, name: &str) -> Result<FilingsList, EdgarError> {
+        let url = format!("{}/submissions/{}", self.config.data_base_url, name);
+
+        let page: FilingsList = self.get(&url, ACCEPT_JSON, ResourceKind::Submissions).await?.json()?;
+        Ok(page)
+    }
+
+    /// URL of a filing's folder in the archives
+    fn filing_folder_url(&self, cik: &str, accession_number: &str) -> String {
+        format!("{}/edgar/data/{}/{}", self.config.archives_base_url, cik, accession_number.replace('-', ""))
+    }
+
+    /// Fetches the directory listing (`index.json`) of a filing folder.
+    pub async fn get_folder_index(&self, cik: &str, accession_number: &str) -> Result<FolderIndex, EdgarError> {
+        let url = format!("{}/index.json", self.filing_folder_url(cik, accession_number));
+
+        let index: FolderIndex = self.get(&url, ACCEPT_JSON, ResourceKind::FilingDocument).await?.json()?;
+        Ok(index)
+    }
+
+    /// Fetches and parses the SGML submission header (`<accession>-index-headers.html`) of a filing.
+    pub async fn get_submission_header(&self, cik: &str, accession_number: &str) -> Result<SubmissionHeader, EdgarError> {
+        let url = format!("{}/{}-index-headers.html", self.filing_folder_url(cik, accession_number), accession_number);
+
+        let response = self.get(&url, ACCEPT_DOCUMENTS, ResourceKind::FilingDocument).await?;
+        parse_submission_header(&response.text())
+    }
+
+    /// Lists every document in a filing (the filing index): names and sizes from the folder's
+    /// `index.json`, type, description and sequence from the SGML header.
+    pub async fn get_filing_index(&self, cik: &str, accession_number: &str) -> Result<Vec<FilingDocument>, EdgarError> {
+        let index = self.get_folder_index(cik, accession_number).await?;
+        let header = self.get_submission_header(cik, accession_number).await?;
+        Ok(index.directory.documents(&header))
+    }
+
+    /// Loads the filing index into `filing.documents`, unless already present.
+    pub async fn load_documents(&self, filing: &mut FilingInfo) -> Result<(), EdgarError> {
+        if filing.documents.is_empty() {
+            filing.documents = self.get_filing_index(&filing.cik, &filing.accession_number).await?;
+            tracing::debug!("Loaded filing index of {}: {} documents", filing.accession_number, filing.documents.len());
+        }
+        Ok(())
+    }
+
+    /// Resolves a single filing from its accession number.
+    ///
+    /// The folder is looked up under the ticker's CIK (if given) and under the CIK embedded in the
+    /// accession number, which is the filer's own CIK unless a filing agent submitted it. CIK, primary
+    /// document, form type and period then come from the folder's `index.json` and SGML header,
+    /// which also provide the filing index (`FilingInfo::documents`).
+    pub async fn find_filing_by_accession(&self, accession_number: &str, ticker: Option<&str>)
+        -> Result<FilingInfo, EdgarError>
+    {
+        let accession = normalize_accession_number(accession_number)
+            .ok_or_else(|| EdgarError::Parse(format!("Invalid accession number '{}'", accession_number)))?;
+
+        let mut candidate_ciks = Vec::new();
+        if let Some(ticker) = ticker {
+            candidate_ciks.push(self.resolve_company(ticker).await?.cik);
+        }
+        let accession_cik = accession[0..10].to_string();
+        if !candidate_ciks.contains(&accession_cik) {
+            candidate_ciks.push(accession_cik);
+        }
+
+        let mut folder = None;
+        for cik in &candidate_ciks {
+            match self.get_folder_index(cik, &accession).await {
+                Ok(index) => {
+                    folder = Some((cik.clone(), index));
+                    break;
+                }
+                Err(EdgarError::FilingDocNotFound(_) | EdgarError::OfflineMiss(_)) => {
+                    tracing::debug!("No folder for {} under CIK {}", accession, cik);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        let (folder_cik, index) = folder.ok_or_else(|| EdgarError::FilingDocNotFound(format!(
+            "no filing folder for accession {} under CIK(s) {} (pass --ticker if a filing agent submitted it)",
+            accession, candidate_ciks.join(", "))))?;
+
+        let header = self.get_submission_header(&folder_cik, &accession).await?;
+        let cik = index.directory.cik().unwrap_or(folder_cik);
+        let primary_doc = header.primary_document()
+            .map(|doc| doc.filename.clone())
+            .filter(|name| index.directory.contains(name))
+            .ok_or_else(|| EdgarError::Parse(format!("Could not determine the primary document of {}", accession)))?;
+
+        let company = match ticker {
+            Some(ticker) => self.resolve_company(ticker).await?,
+            None => self.resolve_company(&cik).await?,
+        };
+        let ticker = match company.ticker.clone() {
+            Some(ticker) => ticker,
+            // Not in the ticker directory: use the company's first ticker, or its CIK if it has none
+            None => self.get_company_submissions(&cik).await?.tickers.into_iter().next()
+                .unwrap_or_else(|| company.output_ticker()),
+        };
+        let form_match = FormMatch::parse(&header.form_type);
+        let filing_date = header.filing_date.clone().unwrap_or_default();
+        let quarterly = QUARTERLY_REPORT_FORMS.contains(&form_match.base_form.as_str());
+        let period = resolve_fiscal_period(header.period.as_deref(), &filing_date,
+                                           header.fiscal_year_end.as_deref().unwrap_or_default(), quarterly);
+
+        tracing::info!("Resolved {} to {} {} filed {} by {} (CIK {})",
+                       accession, header.form_type, primary_doc, filing_date, header.company_name, cik);
+        Ok(FilingInfo {
+            accession_number: accession,
+            filing_date,
+            form_type: header.form_type.clone(),
+            ticker,
+            company_name: header.company_name.clone(),
+            cik,
+            primary_doc,
+            year: period.as_ref().map(|p| p.fiscal_year),
+            is_amendment: form_match.is_amendment,
+            report_date: header.period.clone(),
+            fiscal_quarter: period.and_then(|p| p.fiscal_quarter),
+            documents: index.directory.documents(&header),
+            exchange: company.exchange,
+        })
+    }
+
+    /// Returns `filings.recent` plus every older page that may contain filings in the year range.
+    /// Pages are only fetched when the range reaches past the recent window.
+    async fn filing_pages(&self, submissions: &mut CompanySubmission, start_year: Option<u32>, end_year: Option<u32>)
+        -> Result<Vec<FilingsList>, EdgarError>
+    {
+        // Pages are bucketed by filing date, which can fall outside the fiscal year range
+        let (start_year, end_year) = filing_year_window(start_year, end_year, &submissions.fiscalYearEnd);
+        let mut pages = vec![std::mem::take(&mut submissions.filings.recent)];
+        for file in &submissions.filings.files {
+            if !file.overlaps_years(start_year, end_year) {
+                tracing::trace!("Skipping submissions page {} ({} to {})", file.name, file.filingFrom, file.filingTo);
+                continue;
+            }
+            tracing::info!("Fetching older submissions page {} ({} filings, {} to {})",
+                           file.name, file.filingCount, file.filingFrom, file.filingTo);
+            pages.push(self.get_submissions_page(&file.name).await?);
+        }
+        Ok(pages)
+    }
+
+    /// Finds filings of the forms selected by `forms` (e.g. the 10-K or 10-Q family) for a company
+    /// (ticker, CIK or name, see `resolve_company`) within a fiscal year range.
+    /// Only tests use it; the CLI resolves companies itself to report on each (see `pipeline::batch`).
+    #[cfg(test)]
+    pub async fn find_filings(&self, company: &str, start_year: Option<u32>, end_year: Option<u32>, forms: &FormFilter)
+        -> Result<Vec<FilingInfo>, EdgarError>
+    {
+        let company = self.resolve_company(company).await?;
+        let submissions = self.get_company_submissions(&company.cik).await?;
+        self.filings_from_submissions(&company, submissions, start_year, end_year, forms).await
+    }
+
+    /// Like `find_filings`, for a company already resolved and its submissions already fetched
+    /// (e.g. to check its SIC code first).
+    pub async fn filings_from_submissions(&self, company: &CompanyEntry, mut submissions: CompanySubmission,
+                                          start_year: Option<u32>, end_year: Option<u32>, forms: &FormFilter)
+        -> Result<Vec<FilingInfo>, EdgarError>
+    {
+        let cik = company.cik.clone();
+        // Output is named after the ticker, or after the CIK for companies without one
+        let ticker = company.ticker.clone()
+            .or_else(|| submissions.tickers.first().cloned())
+            .unwrap_or_else(|| company.output_ticker());
+        tracing::info!("Resolved company to {} (CIK {}, {}){}", submissions.name, cik, ticker,
+                       company.exchange.as_deref().map(|e| format!(" listed on {}", e)).unwrap_or_default());
+        let fiscal_year_end = submissions.fiscalYearEnd.clone();
+
+        let mut filings: Vec<FilingInfo> = Vec::new();
+
+        // Process recent filings, then any older pages
+        for page in self.filing_pages(&mut submissions, start_year, end_year).await? {
+            for i in 0..page.accessionNumber.len() {
+                let form = page.form.get(i)
+                    .ok_or_else(|| EdgarError::Parse("Missing form type".to_string()))?;
+
+                // Filter for the requested forms (and amendments, per policy)
+                let Some(form_match) = forms.matches(form) else {
+                    continue;
+                };
+                let filing_date = page.filingDate.get(i)
+                    .ok_or_else(|| EdgarError::Parse("Missing filing date".to_string()))?;
+
+                // Resolve the fiscal year from the period end (falling back to the filing date)
+                let report_date = page.reportDate.get(i).map(String::as_str);
+                let quarterly = QUARTERLY_REPORT_FORMS.contains(&form_match.base_form.as_str());
+                let period = resolve_fiscal_period(report_date, filing_date, &fiscal_year_end, quarterly)
+                    .ok_or_else(|| EdgarError::Parse(format!("Invalid filing date '{}'", filing_date)))?;
+                let year = period.fiscal_year;
+
+                // Apply fiscal year filtering if specified
+                if start_year.is_some_and(|start| year < start) || end_year.is_some_and(|end| year > end) {
+                    continue;
+                }
+
+                let acc_num = page.accessionNumber.get(i)
                     .ok_or_else(|| EdgarError::Parse("Missing accession number".to_string()))?;
-                let primary_doc = submissions.filings.recent.primaryDocument.get(i)
+                // Pages shouldn't overlap, but never process the same filing twice
+                if filings.iter().any(|f| &f.accession_number == acc_num) {
+                    continue;
+                }
+                let primary_doc = page.primaryDocument.get(i)
                     .ok_or_else(|| EdgarError::Parse("Missing primary document".to_string()))?;
-                
+
                 filings.push(FilingInfo {
                     accession_number: acc_num.clone(),
                     filing_date: filing_date.clone(),
                     form_type: form.clone(),
-                    ticker: ticker.to_uppercase(),
-                    company_name: submissions.name.clone(),
+                    ticker: ticker.clone(),
+                    company_name: submissions.name_on(filing_date).to_string(),
                     cik: cik.clone(),
                     primary_doc: primary_doc.clone(),
                     year: Some(year),
+                    is_amendment: form_match.is_amendment,
+                    report_date: period.period_end,
+                    fiscal_quarter: period.fiscal_quarter,
+                    documents: Vec::new(),
+                    exchange: company.exchange.clone(),
                 });
             }
         }
+
+        let mut filings = forms.apply_amendment_policy(filings);
+
+        // Sort by year, then quarter (newest first)
+        filings.sort_by_key(|f| std::cmp::Reverse((f.year.unwrap_or(0), f.fiscal_quarter.unwrap_or(0))));
+
+        Ok(filings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edgar::cache::CacheTtls;
+    use crate::edgar::forms::{AmendmentPolicy, ReportType};
+    use crate::edgar::mock_server::{MockResponse, MockServer};
+    use crate::edgar::offline::{offline_client, FIXTURES_DIR};
+    use crate::extractors::section::{ten_q_section, DomExtractor, FilingContext, TEN_K_ITEM_8};
+    use crate::storage::StorageManager;
+
+    fn annual_reports() -> FormFilter {
+        FormFilter::for_report_type(ReportType::Annual, AmendmentPolicy::OriginalsOnly)
+    }
+
+    #[tokio::test]
+    async fn test_offline_pipeline_from_fixtures() {
+        let edgar = offline_client();
+        // Fiscal 2023 was filed in February 2024
+        let filings = edgar.find_filings("tstc", Some(2023), Some(2023), &annual_reports()).await.unwrap();
+        assert_eq!(filings.len(), 1);
+        let filing = &filings[0];
+        assert_eq!(filing.year, Some(2023));
+        assert_eq!(filing.report_date.as_deref(), Some("2023-12-31"));
+        assert_eq!(filing.cik, "0001234567");
+        assert_eq!(filing.accession_number, "0001234567-24-000010");
+
+        let content = edgar.download_filing_doc(&filing.primary_doc_url(&edgar.config().archives_base_url)).await.unwrap();
+        let context = FilingContext {
+            ticker: filing.ticker.clone(),
+            company_name: filing.company_name.clone(),
+            filing_year: filing.year.unwrap(),
+            form_type: filing.form_type.clone(),
+            accession_number: filing.accession_number.clone(),
+            is_amendment: filing.is_amendment,
+            fiscal_quarter: filing.fiscal_quarter,
+            period_end: filing.report_date.clone(),
+        };
+        let section = DomExtractor::new().extract_section(&content, &TEN_K_ITEM_8, &context, 500).unwrap();
+        assert!(section.content_html.contains("CONSOLIDATED BALANCE SHEETS"));
+        assert!(!section.content_html.contains("Item 9."));
+
+        let output = tempfile::tempdir().unwrap();
+        let storage = StorageManager::new(output.path()).unwrap();
+        assert!(storage.save_section(&section).unwrap().exists());
+        assert!(storage.save_section_metadata(&section).unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn test_company_resolved_from_cik_or_name() {
+        let server = MockServer::serve_dir(FIXTURES_DIR).await;
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap();
+
+        for identifier in ["1234567", "CIK0001234567", "test widgets", "tstc"] {
+            let filings = edgar.find_filings(identifier, Some(2023), Some(2023), &annual_reports()).await.unwrap();
+            assert_eq!(filings.len(), 1, "{}", identifier);
+            assert_eq!((filings[0].ticker.as_str(), filings[0].exchange.as_deref()), ("TSTC", Some("Nasdaq")));
+        }
+        let directory_fetches = server.requests().iter().filter(|r| r.path.ends_with("company_tickers_exchange.json")).count();
+        assert_eq!(directory_fetches, 1, "The directory is loaded once per client");
+
+        let err = edgar.find_filings("Nonexistent Mining", None, None, &annual_reports()).await.unwrap_err();
+        assert!(matches!(err, EdgarError::CompanyNotFound(_)), "Unexpected error: {:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_former_names() {
+        let edgar = offline_client();
+        // Renamed in 2015; the 10-K405 filed in 2001 carries the name of the time
+        let filings = edgar.find_filings("Test Gadgets", None, None, &annual_reports()).await.unwrap();
+        let names: Vec<(&str, &str)> = filings.iter().map(|f| (f.filing_date.as_str(), f.company_name.as_str())).collect();
+        assert_eq!(names[0], ("2024-02-15", "Test Widgets Corp"));
+        assert_eq!(names.last(), Some(&("2001-03-20", "Test Gadgets Inc")));
+        assert!(filings.iter().all(|f| f.ticker == "TSTC"), "Still listed, so the current ticker is used");
+
+        let delisted = edgar.resolve_company("vanished railways").await.unwrap();
+        assert_eq!((delisted.cik.as_str(), delisted.ticker.as_deref()), ("0009876543", None));
+        assert_eq!(delisted.output_ticker(), "9876543");
+    }
+
+    #[tokio::test]
+    async fn test_filing_resolved_by_accession_number() {
+        let edgar = offline_client();
+        let filing = edgar.find_filing_by_accession("000123456724000010", None).await.unwrap();
+        assert_eq!(filing.accession_number, "0001234567-24-000010");
+        assert_eq!(filing.cik, "0001234567");
+        assert_eq!(filing.ticker, "TSTC", "Ticker comes from the company's submissions");
+        assert_eq!(filing.form_type, "10-K");
+        assert_eq!(filing.primary_doc, "tstc-20231231.htm");
+        assert_eq!((filing.year, filing.report_date.as_deref()), (Some(2023), Some("2023-12-31")));
+        assert_eq!(filing.filing_date, "2024-02-15");
+        assert_eq!(filing.documents.len(), 8, "Four submitted documents plus four generated files");
+        let instance = filing.document_of_type("ex-101.ins").expect("XBRL instance listed");
+        assert_eq!((instance.filename.as_str(), instance.size), ("tstc-20231231_htm.xml", Some(20480)));
+
+        let content = edgar.download_filing_doc(&filing.primary_doc_url(&edgar.config().archives_base_url)).await.unwrap();
+        assert!(content.contains("Item 8. Financial Statements and Supplementary Data"));
+
+        let missing = edgar.find_filing_by_accession("0009999999-24-000001", None).await.unwrap_err();
+        assert!(matches!(missing, EdgarError::FilingDocNotFound(_)), "Unexpected error: {:?}", missing);
+        assert!(matches!(edgar.find_filing_by_accession("12345", None).await, Err(EdgarError::Parse(_))));
+    }
+
+    #[tokio::test]
+    async fn test_full_submission_recovers_section_missing_from_primary_document() {
+        let edgar = offline_client();
+        let filings = edgar.find_filings("TSTC", Some(2000), Some(2000), &annual_reports()).await.unwrap();
+        assert_eq!(filings.len(), 1);
+        let filing = &filings[0];
+        let context = FilingContext { ticker: filing.ticker.clone(), filing_year: 2000, ..FilingContext::default() };
+        let extractor = DomExtractor::new();
+
+        // The primary document only holds Part I
+        let primary = edgar.download_filing_doc(&filing.primary_doc_url(&edgar.config().archives_base_url)).await.unwrap();
+        assert!(extractor.extract_section(&primary, &TEN_K_ITEM_8, &context, 200).is_err());
+
+        let documents = edgar.download_full_submission(filing).await.unwrap();
+        assert_eq!(documents.len(), 3);
+        let report: Vec<&SubmissionDocument> = documents.iter().filter(|d| d.is_report_part(&filing.form_type)).collect();
+        assert_eq!(report.len(), 2, "Both parts of the 10-K405, but not the EX-27 schedule");
+        let merged = crate::edgar::sgml::merge_documents(&report);
+        let section = extractor.extract_section(&merged, &TEN_K_ITEM_8, &context, 200).unwrap();
+        assert!(section.content_html.contains("BALANCE SHEETS"));
+        assert!(!section.content_html.contains("CHANGES IN AND DISAGREEMENTS"));
+    }
+
+    #[tokio::test]
+    async fn test_offline_miss_is_an_error() {
+        let edgar = offline_client();
+        let err = edgar.get_company_submissions("0009999999").await.unwrap_err();
+        assert!(matches!(err, EdgarError::OfflineMiss(_)), "Unexpected error: {:?}", err);
     }
-    
-    // Sort by year (newest first)
-    filings.sort_by(|a, b| b.year.unwrap_or(0).cmp(&a.year.unwrap_or(0)));
-    
-    Ok(filings)
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_offline_prefers_cache_even_when_stale() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let ttls = CacheTtls { ticker_directory: Some(Duration::ZERO), submissions: Some(Duration::ZERO) };
+        let cache = HttpCache::new(cache_dir.path(), ttls).unwrap();
+        let url = format!("{}/company_tickers_exchange.json", EdgarConfig::default().files_base_url);
+        cache.put(&url, &HeaderMap::new(), br#"{"fields":["cik","name","ticker","exchange"],"data":[[42,"Cached","TSTC",null]]}"#).unwrap();
+
+        let edgar = EdgarClient::new(EdgarConfig::default()).unwrap().with_cache(cache).offline(None);
+        assert_eq!(edgar.resolve_company("TSTC").await.unwrap().cik, "0000000042");
+    }
+
+    /// Config whose base URLs point at a mock server laid out like the fixtures directory.
+    fn mock_config(server: &MockServer) -> EdgarConfig {
+        EdgarConfig {
+            archives_base_url: format!("{}/www.sec.gov/Archives/", server.url()),
+            data_base_url: format!("{}/data.sec.gov", server.url()),
+            files_base_url: format!("{}/www.sec.gov/files", server.url()),
+            requests_per_second: 10,
+            retry_base_delay_ms: 5,
+            retry_max_delay_ms: 50,
+            ..EdgarConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_against_mock_server() {
+        let server = MockServer::serve_dir(FIXTURES_DIR).await;
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap();
+
+        let filings = edgar.find_filings("TSTC", None, None, &annual_reports()).await.unwrap();
+        assert_eq!(filings.len(), 4, "Two recent 10-Ks plus a 10-K and a 10-K405 from the older submissions page");
+        assert_eq!(filings[2].accession_number, "0001234567-22-000008");
+        assert_eq!(filings[3].form_type, "10-K405");
+        assert!(filings.iter().all(|f| !f.is_amendment), "Amendments are excluded by default");
+        let url = filings[0].primary_doc_url(&edgar.config().archives_base_url);
+        assert!(url.starts_with(server.url()), "Document URL should use the overridden host: {}", url);
+        let content = edgar.download_filing_doc(&url).await.unwrap();
+        assert!(content.contains("Item 8. Financial Statements and Supplementary Data"));
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, vec![
+            "/www.sec.gov/files/company_tickers_exchange.json",
+            "/data.sec.gov/submissions/CIK0001234567.json",
+            "/data.sec.gov/submissions/CIK0001234567-submissions-001.json",
+            "/www.sec.gov/Archives/edgar/data/0001234567/000123456724000010/tstc-20231231.htm",
+        ]);
+        for request in server.requests() {
+            assert_eq!(request.headers.get("user-agent").map(String::as_str), Some("Your Company Name your.email@example.com"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_older_pages_fetched_only_when_needed() {
+        let server = MockServer::serve_dir(FIXTURES_DIR).await;
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap();
+
+        let recent_only = edgar.find_filings("TSTC", Some(2023), None, &annual_reports()).await.unwrap();
+        assert_eq!(recent_only.len(), 1);
+        assert!(!server.requests().iter().any(|r| r.path.contains("submissions-001")), "Recent window covers fiscal 2023+");
+
+        let older = edgar.find_filings("TSTC", Some(2021), Some(2021), &annual_reports()).await.unwrap();
+        assert_eq!(older.len(), 1);
+        assert_eq!(older[0].filing_date, "2022-02-11");
+        assert!(server.requests().iter().any(|r| r.path.contains("submissions-001")));
+    }
+
+    #[tokio::test]
+    async fn test_amendment_policies() {
+        let edgar = offline_client();
+        let policy = |amendments| FormFilter::for_report_type(ReportType::Annual, amendments);
+
+        let all = edgar.find_filings("TSTC", Some(2023), Some(2023), &policy(AmendmentPolicy::KeepAll)).await.unwrap();
+        assert_eq!(all.len(), 2);
+        let amendment = all.iter().find(|f| f.is_amendment).expect("10-K/A should be listed");
+        assert_eq!(amendment.form_type, "10-K/A");
+        assert_eq!(amendment.report_date.as_deref(), Some("2023-12-31"));
+
+        let latest = edgar.find_filings("TSTC", Some(2023), Some(2023), &policy(AmendmentPolicy::PreferLatest)).await.unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].accession_number, "0001234567-24-000031");
+    }
+
+    #[tokio::test]
+    async fn test_quarterly_pipeline_from_fixtures() {
+        let edgar = offline_client();
+        let quarterly = FormFilter::for_report_type(ReportType::Quarterly, AmendmentPolicy::OriginalsOnly);
+        let filings = edgar.find_filings("TSTC", None, None, &quarterly).await.unwrap();
+        let quarters: Vec<_> = filings.iter().map(|f| (f.year, f.fiscal_quarter)).collect();
+        assert_eq!(quarters, [(Some(2023), Some(3)), (Some(2021), Some(3))]);
+
+        let filing = &filings[0];
+        let content = edgar.download_filing_doc(&filing.primary_doc_url(&edgar.config().archives_base_url)).await.unwrap();
+        let context = FilingContext {
+            ticker: filing.ticker.clone(),
+            filing_year: filing.year.unwrap(),
+            form_type: filing.form_type.clone(),
+            fiscal_quarter: filing.fiscal_quarter,
+            ..FilingContext::default()
+        };
+        let spec = ten_q_section("Part I Item 1").unwrap();
+        let section = DomExtractor::new().extract_section(&content, spec, &context, 500).unwrap();
+        assert!(section.content_html.contains("CONDENSED CONSOLIDATED BALANCE SHEETS"));
+        assert!(!section.content_html.contains("third quarter increased"));
+
+        let output = tempfile::tempdir().unwrap();
+        let path = StorageManager::new(output.path()).unwrap().save_section(&section).unwrap();
+        assert!(path.ends_with("TSTC/2023/Q3/TSTC_2023Q3_PartI_Item1.html"), "Unexpected path: {}", path.display());
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried() {
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let server = MockServer::start(move |_| {
+            match counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                0 => MockResponse::status(503),
+                1 => MockResponse::status(403).header("Retry-After", "0"),
+                _ => MockResponse::ok("<html>ok</html>"),
+            }
+        }).await;
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap();
+
+        let body = edgar.download_filing_doc(&format!("{}/doc.htm", server.url())).await.unwrap();
+        assert_eq!(body, "<html>ok</html>");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_exhausted_reports_attempts() {
+        let server = MockServer::start(|_| MockResponse::status(502)).await;
+        let config = EdgarConfig { max_retries: 2, ..mock_config(&server) };
+        let edgar = EdgarClient::new(config).unwrap();
+
+        let err = edgar.download_filing_doc(&format!("{}/doc.htm", server.url())).await.unwrap_err();
+        match err {
+            EdgarError::RetriesExhausted { attempts, source } => {
+                assert_eq!(attempts, 3);
+                assert!(matches!(*source, EdgarError::Http(StatusCode::BAD_GATEWAY)));
+            }
+            other => panic!("Unexpected error: {:?}", other),
+        }
+
+        // Fatal errors are not retried
+        let server = MockServer::start(|_| MockResponse::status(404)).await;
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap();
+        let err = edgar.download_filing_doc(&format!("{}/missing.htm", server.url())).await.unwrap_err();
+        assert!(matches!(err, EdgarError::FilingDocNotFound(_)));
+        assert_eq!(server.requests().len(), 1);
+
+        // ...also when they follow a transient failure
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let server = MockServer::start(move |_| {
+            match counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                0 => MockResponse::status(503),
+                _ => MockResponse::status(404),
+            }
+        }).await;
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap();
+        let err = edgar.download_filing_doc(&format!("{}/missing.htm", server.url())).await.unwrap_err();
+        assert!(matches!(err, EdgarError::FilingDocNotFound(_)), "Unexpected error: {:?}", err);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_cache_entry_is_revalidated() {
+        let server = MockServer::start(|req| {
+            if req.headers.get("if-none-match").map(String::as_str) == Some("\"v1\"") {
+                MockResponse::status(304)
+            } else {
+                MockResponse::ok(r#"{"fields":["cik","name","ticker","exchange"],"data":[[1234567,"Test Widgets Corp","TSTC","Nasdaq"]]}"#)
+                    .header("ETag", "\"v1\"")
+            }
+        }).await;
+        let cache_dir = tempfile::tempdir().unwrap();
+        let ttls = CacheTtls { ticker_directory: Some(Duration::ZERO), submissions: Some(Duration::ZERO) };
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap()
+            .with_cache(HttpCache::new(cache_dir.path(), ttls).unwrap());
+
+        assert_eq!(edgar.resolve_company("TSTC").await.unwrap().cik, "0001234567");
+        // A new client, as the directory is only loaded once per client
+        let edgar = EdgarClient { directory: Arc::default(), ..edgar };
+        assert_eq!(edgar.resolve_company("TSTC").await.unwrap().cik, "0001234567");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].headers.contains_key("if-none-match"));
+        assert_eq!(requests[1].headers.get("if-none-match").map(String::as_str), Some("\"v1\""));
+    }
+
+    #[tokio::test]
+    async fn test_filing_documents_are_fetched_once() {
+        let server = MockServer::serve_dir(FIXTURES_DIR).await;
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(cache_dir.path(), CacheTtls::from_config(&EdgarConfig::default())).unwrap();
+        let edgar = EdgarClient::new(mock_config(&server)).unwrap().with_cache(cache);
+
+        let url = format!("{}/www.sec.gov/Archives/edgar/data/0001234567/000123456724000010/tstc-20231231.htm", server.url());
+        let first = edgar.download_filing_doc(&url).await.unwrap();
+        let second = edgar.download_filing_doc(&url).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(server.requests().len(), 1, "Accession-addressed documents are never re-fetched");
+    }
+}
@@ -0,0 +1,286 @@
+// src/edgar/config.rs
+use crate::utils::error::AppError;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default config file looked up in the working directory when `--config` is not given.
+pub const DEFAULT_CONFIG_FILE: &str = "sec_extractor.toml";
+/// Environment variable pointing at a config file (overridden by `--config`).
+pub const CONFIG_PATH_ENV: &str = "SEC_EXTRACTOR_CONFIG";
+
+// Placeholder identity values shipped as defaults. SEC blocks generic User-Agents,
+// so leaving any of these in place is treated as a configuration error.
+const PLACEHOLDER_USER_AGENT: &str = "Your Company Name";
+const PLACEHOLDER_CONTACT_EMAIL: &str = "your.email@example.com";
+
+/// Settings controlling how we talk to SEC EDGAR.
+/// Resolved in order: defaults < config file (`[edgar]` table) < environment < CLI flags.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EdgarConfig {
+    /// Organisation / person name sent in the User-Agent (e.g. "Acme Research Jane Doe")
+    pub user_agent: String,
+    /// Contact email appended to the User-Agent, as SEC requires
+    pub contact_email: String,
+    /// Maximum requests per second (SEC allows at most 10)
+    pub requests_per_second: u32,
+    /// Total request timeout in seconds
+    pub timeout_secs: u64,
+    /// Connection establishment timeout in seconds
+    pub connect_timeout_secs: u64,
+    /// Retries after the first attempt for transient failures (5xx, 403 rate limit, timeouts)
+    pub max_retries: u32,
+    /// Initial backoff delay in milliseconds, doubled on every retry
+    pub retry_base_delay_ms: u64,
+    /// Upper bound for a single backoff delay (also caps `Retry-After`)
+    pub retry_max_delay_ms: u64,
+    /// How long a cached `company_tickers_exchange.json` is used before revalidating
+    pub cache_ttl_tickers_secs: u64,
+    /// How long a cached submissions JSON is used before revalidating
+    pub cache_ttl_submissions_secs: u64,
+    /// Base URL for filing archives (`{archives_base_url}/edgar/data/...`)
+    pub archives_base_url: String,
+    /// Base URL for the JSON data API (`{data_base_url}/submissions/...`)
+    pub data_base_url: String,
+    /// Base URL for static files such as `company_tickers_exchange.json`
+    pub files_base_url: String,
+}
+
+impl Default for EdgarConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: PLACEHOLDER_USER_AGENT.to_string(),
+            contact_email: PLACEHOLDER_CONTACT_EMAIL.to_string(),
+            requests_per_second: 8, // Stay a little under the SEC limit of 10
+            timeout_secs: 60,
+            connect_timeout_secs: 10,
+            max_retries: 4,
+            retry_base_delay_ms: 1_000,
+            retry_max_delay_ms: 60_000,
+            cache_ttl_tickers_secs: 24 * 60 * 60,
+            cache_ttl_submissions_secs: 60 * 60,
+            archives_base_url: "https://www.sec.gov/Archives".to_string(),
+            data_base_url: "https://data.sec.gov".to_string(),
+            files_base_url: "https://www.sec.gov/files".to_string(),
+        }
+    }
+}
+
+/// On-disk layout of the config file. Only the `[edgar]` table is read for now.
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    edgar: EdgarConfig,
+}
+
+impl EdgarConfig {
+    /// Loads defaults, then the config file (if any), then environment variables.
+    /// `explicit_path` comes from `--config`; a missing explicit file is an error,
+    /// whereas a missing default `sec_extractor.toml` is silently ignored.
+    pub fn load(explicit_path: Option<&Path>) -> Result<Self, AppError> {
+        let mut config = match Self::resolve_config_path(explicit_path)? {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Parses the `[edgar]` table from a TOML config file.
+    pub fn from_file(path: &Path) -> Result<Self, AppError> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Config(format!("Failed to read config file {}: {}", path.display(), e)))?;
+        let file: ConfigFile = toml::from_str(&raw)
+            .map_err(|e| AppError::Config(format!("Invalid config file {}: {}", path.display(), e)))?;
+        tracing::info!("Loaded EDGAR configuration from {}", path.display());
+        Ok(file.edgar)
+    }
+
+    fn resolve_config_path(explicit_path: Option<&Path>) -> Result<Option<PathBuf>, AppError> {
+        if let Some(path) = explicit_path {
+            if !path.exists() {
+                return Err(AppError::Config(format!("Config file not found: {}", path.display())));
+            }
+            return Ok(Some(path.to_path_buf()));
+        }
+        if let Ok(path) = std::env::var(CONFIG_PATH_ENV) {
+            let path = PathBuf::from(path);
+            if !path.exists() {
+                return Err(AppError::Config(format!("Config file from {} not found: {}", CONFIG_PATH_ENV, path.display())));
+            }
+            return Ok(Some(path));
+        }
+        let default_path = PathBuf::from(DEFAULT_CONFIG_FILE);
+        Ok(default_path.exists().then_some(default_path))
+    }
+
+    /// Overrides fields from `EDGAR_*` environment variables.
+    fn apply_env(&mut self) -> Result<(), AppError> {
+        self.apply_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Applies overrides from a variable lookup (split out so tests don't touch the process env).
+    fn apply_vars<F>(&mut self, lookup: F) -> Result<(), AppError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(v) = lookup("EDGAR_USER_AGENT") { self.user_agent = v; }
+        if let Some(v) = lookup("EDGAR_CONTACT_EMAIL") { self.contact_email = v; }
+        if let Some(v) = lookup("EDGAR_REQUESTS_PER_SECOND") {
+            self.requests_per_second = parse_env_number("EDGAR_REQUESTS_PER_SECOND", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_TIMEOUT_SECS") {
+            self.timeout_secs = parse_env_number("EDGAR_TIMEOUT_SECS", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_CONNECT_TIMEOUT_SECS") {
+            self.connect_timeout_secs = parse_env_number("EDGAR_CONNECT_TIMEOUT_SECS", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_MAX_RETRIES") {
+            self.max_retries = parse_env_number("EDGAR_MAX_RETRIES", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_RETRY_BASE_DELAY_MS") {
+            self.retry_base_delay_ms = parse_env_number("EDGAR_RETRY_BASE_DELAY_MS", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_RETRY_MAX_DELAY_MS") {
+            self.retry_max_delay_ms = parse_env_number("EDGAR_RETRY_MAX_DELAY_MS", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_CACHE_TTL_TICKERS_SECS") {
+            self.cache_ttl_tickers_secs = parse_env_number("EDGAR_CACHE_TTL_TICKERS_SECS", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_CACHE_TTL_SUBMISSIONS_SECS") {
+            self.cache_ttl_submissions_secs = parse_env_number("EDGAR_CACHE_TTL_SUBMISSIONS_SECS", &v)?;
+        }
+        if let Some(v) = lookup("EDGAR_ARCHIVES_BASE_URL") { self.archives_base_url = v; }
+        if let Some(v) = lookup("EDGAR_DATA_BASE_URL") { self.data_base_url = v; }
+        if let Some(v) = lookup("EDGAR_FILES_BASE_URL") { self.files_base_url = v; }
+        Ok(())
+    }
+
+    /// Checks the final configuration. Must be called before any request is made.
+    pub fn validate(&self) -> Result<(), AppError> {
+        self.validate_identity()?;
+        self.validate_settings()
+    }
+
+    /// Rejects a missing or placeholder User-Agent identity.
+    /// Skipped in offline mode, where no request ever reaches SEC.
+    pub fn validate_identity(&self) -> Result<(), AppError> {
+        let user_agent = self.user_agent.trim();
+        let contact_email = self.contact_email.trim();
+
+        if user_agent.is_empty() || user_agent == PLACEHOLDER_USER_AGENT {
+            return Err(AppError::Config(
+                "EDGAR user agent is not set. Provide your organisation/name via --user-agent, \
+                 EDGAR_USER_AGENT or `user_agent` in the [edgar] config table".to_string(),
+            ));
+        }
+        if contact_email.is_empty()
+            || contact_email == PLACEHOLDER_CONTACT_EMAIL
+            || contact_email.ends_with("@example.com")
+            || !contact_email.contains('@')
+        {
+            return Err(AppError::Config(format!(
+                "EDGAR contact email '{}' is missing or a placeholder. Provide a real address via \
+                 --contact-email, EDGAR_CONTACT_EMAIL or `contact_email` in the config file",
+                contact_email
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks rate, retry and URL settings.
+    pub fn validate_settings(&self) -> Result<(), AppError> {
+        if self.requests_per_second == 0 || self.requests_per_second > 10 {
+            return Err(AppError::Config(format!(
+                "requests_per_second must be between 1 and 10 (SEC fair access limit), got {}",
+                self.requests_per_second
+            )));
+        }
+        if self.retry_base_delay_ms > self.retry_max_delay_ms {
+            return Err(AppError::Config(format!(
+                "retry_base_delay_ms ({}) must not exceed retry_max_delay_ms ({})",
+                self.retry_base_delay_ms, self.retry_max_delay_ms
+            )));
+        }
+        for (name, url) in [
+            ("archives_base_url", &self.archives_base_url),
+            ("data_base_url", &self.data_base_url),
+            ("files_base_url", &self.files_base_url),
+        ] {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(AppError::Config(format!("{} must be an http(s) URL, got '{}'", name, url)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Full User-Agent header value in the format SEC asks for: "Name email@domain".
+    pub fn user_agent_header(&self) -> String {
+        format!("{} {}", self.user_agent.trim(), self.contact_email.trim())
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+}
+
+fn parse_env_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, AppError> {
+    value.trim().parse::<T>()
+        .map_err(|_| AppError::Config(format!("{} must be a number, got '{}'", name, value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_config() -> EdgarConfig {
+        EdgarConfig {
+            user_agent: "Acme Research Jane Doe".to_string(),
+            contact_email: "jane@acme.test".to_string(),
+            ..EdgarConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_default_identity_is_rejected() {
+        assert!(EdgarConfig::default().validate().is_err(), "Placeholder identity must not validate");
+        assert!(valid_config().validate().is_ok());
+
+        let mut config = valid_config();
+        config.contact_email = "someone@example.com".to_string();
+        assert!(config.validate().is_err(), "example.com address should be treated as placeholder");
+    }
+
+    #[test]
+    fn test_file_then_env_precedence() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            [edgar]
+            user_agent = "File Corp"
+            contact_email = "file@corp.test"
+            requests_per_second = 5
+            "#,
+        ).unwrap();
+        let mut config = file.edgar;
+        assert_eq!(config.user_agent, "File Corp");
+        assert_eq!(config.timeout_secs, 60, "Unspecified fields keep their defaults");
+
+        config.apply_vars(|name| match name {
+            "EDGAR_USER_AGENT" => Some("Env Corp".to_string()),
+            "EDGAR_REQUESTS_PER_SECOND" => Some("9".to_string()),
+            _ => None,
+        }).unwrap();
+        assert_eq!(config.user_agent, "Env Corp");
+        assert_eq!(config.contact_email, "file@corp.test");
+        assert_eq!(config.requests_per_second, 9);
+        assert_eq!(config.user_agent_header(), "Env Corp file@corp.test");
+
+        let bad = config.apply_vars(|name| (name == "EDGAR_TIMEOUT_SECS").then(|| "soon".to_string()));
+        assert!(bad.is_err());
+    }
+}
@@ -0,0 +1,291 @@
+// src/edgar/directory.rs
+//! Company directory built from SEC's `company_tickers_exchange.json`, resolving the
+//! identifiers accepted on the command line (ticker, CIK or company name) to a CIK.
+use crate::utils::error::EdgarError;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A raw CIK, optionally prefixed with "CIK" (e.g. "320193", "0000320193", "CIK0000320193")
+static CIK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:CIK)?\s*(\d{1,10})$").expect("Failed to compile CIK_RE")
+});
+
+/// Legal-form words ignored when comparing company names
+const NAME_STOP_WORDS: &[&str] = &[
+    "the", "inc", "incorporated", "corp", "corporation", "co", "company", "ltd", "limited",
+    "plc", "llc", "lp", "sa", "nv", "ag", "se", "de",
+];
+
+/// Lowest name-match score accepted for a fuzzy company-name lookup
+const MIN_NAME_SCORE: f64 = 0.5;
+
+/// One listing in the directory. A company with several share classes has one entry per ticker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompanyEntry {
+    /// Zero-padded 10-digit CIK
+    pub cik: String,
+    pub name: String,
+    /// SEC-style ticker ("BRK-B"); `None` for a CIK that isn't in the directory
+    pub ticker: Option<String>,
+    /// Listing exchange as reported by SEC (e.g. "Nasdaq", "NYSE", "OTC")
+    pub exchange: Option<String>,
+}
+
+impl CompanyEntry {
+    /// Name used for output paths: the ticker, or the CIK without leading zeros if there is none.
+    pub fn output_ticker(&self) -> String {
+        self.ticker.clone().unwrap_or_else(|| self.cik.trim_start_matches('0').to_string())
+    }
+}
+
+/// Column-oriented layout of `company_tickers_exchange.json`
+#[derive(Debug, Deserialize)]
+struct ExchangeFile {
+    fields: Vec<String>,
+    data: Vec<Vec<serde_json::Value>>,
+}
+
+/// In-memory index over the ticker directory.
+#[derive(Debug, Default)]
+pub struct CompanyDirectory {
+    entries: Vec<CompanyEntry>,
+    by_ticker: HashMap<String, usize>,
+    by_cik: HashMap<String, usize>,
+}
+
+impl CompanyDirectory {
+    /// Parses `company_tickers_exchange.json` (`{"fields": [...], "data": [[cik, name, ticker, exchange], ...]}`).
+    pub fn from_json(body: &[u8]) -> Result<Self, EdgarError> {
+        let file: ExchangeFile = serde_json::from_slice(body)
+            .map_err(|e| EdgarError::Parse(format!("Invalid company ticker directory: {}", e)))?;
+        let column = |name: &str| file.fields.iter().position(|f| f == name);
+        let cik_col = column("cik").ok_or_else(|| EdgarError::Parse("Ticker directory has no 'cik' field".to_string()))?;
+        let (name_col, ticker_col, exchange_col) = (column("name"), column("ticker"), column("exchange"));
+
+        let text = |row: &[serde_json::Value], col: Option<usize>| {
+            col.and_then(|c| row.get(c)).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty()).map(String::from)
+        };
+        let entries = file.data.iter()
+            .filter_map(|row| {
+                let cik = row.get(cik_col).and_then(|v| v.as_u64())?;
+                Some(CompanyEntry {
+                    cik: format!("{:010}", cik),
+                    name: text(row, name_col).unwrap_or_default(),
+                    ticker: text(row, ticker_col).map(|t| normalize_ticker(&t)),
+                    exchange: text(row, exchange_col),
+                })
+            })
+            .collect();
+        let directory = Self::new(entries);
+        if directory.is_empty() {
+            return Err(EdgarError::Parse("Ticker directory has no entries".to_string()));
+        }
+        Ok(directory)
+    }
+
+    /// Parses EDGAR's `cik-lookup-data.txt` (`NAME:CIK:` per line), which lists every name a CIK
+    /// has filed under, former names included. Its entries have no ticker or exchange.
+    pub fn from_cik_lookup(text: &str) -> Self {
+        let entries = text.lines()
+            .filter_map(|line| {
+                // Names may contain ':' themselves, so split from the right
+                let (name, cik) = line.trim_end().strip_suffix(':')?.rsplit_once(':')?;
+                Some(CompanyEntry { cik: parse_cik(cik)?, name: name.trim().to_string(), ticker: None, exchange: None })
+            })
+            .collect();
+        Self::new(entries)
+    }
+
+    pub fn new(entries: Vec<CompanyEntry>) -> Self {
+        let mut by_ticker = HashMap::new();
+        let mut by_cik = HashMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            if let Some(ticker) = &entry.ticker {
+                by_ticker.entry(ticker.clone()).or_insert(i);
+            }
+            // SEC lists the primary share class first
+            by_cik.entry(entry.cik.clone()).or_insert(i);
+        }
+        Self { entries, by_ticker, by_cik }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Looks up a ticker in any share-class notation ("BRK.B", "BRK/B", "brk-b").
+    pub fn by_ticker(&self, ticker: &str) -> Option<&CompanyEntry> {
+        self.by_ticker.get(&normalize_ticker(ticker)).map(|&i| &self.entries[i])
+    }
+
+    /// Looks up a CIK, with or without leading zeros.
+    pub fn by_cik(&self, cik: &str) -> Option<&CompanyEntry> {
+        let cik = parse_cik(cik)?;
+        self.by_cik.get(&cik).map(|&i| &self.entries[i])
+    }
+
+    /// Companies whose name matches `query`, best match first, one entry per CIK.
+    pub fn search_name(&self, query: &str) -> Vec<(&CompanyEntry, f64)> {
+        let query_tokens = name_tokens(query);
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(&CompanyEntry, f64)> = Vec::new();
+        for entry in &self.entries {
+            let score = name_score(&query_tokens, &name_tokens(&entry.name));
+            if score >= MIN_NAME_SCORE && !matches.iter().any(|(m, _)| m.cik == entry.cik) {
+                matches.push((entry, score));
+            }
+        }
+        // Stable sort keeps the directory's order (roughly by market value) among equal scores
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches
+    }
+
+    /// Resolves a CIK, a ticker or a company name, tried in that order.
+    ///
+    /// A well-formed CIK always resolves, even if it isn't in the directory (companies without
+    /// listed securities); its entry then has no ticker. A name that matches several companies
+    /// equally well is an error rather than a guess.
+    pub fn resolve(&self, identifier: &str) -> Result<CompanyEntry, EdgarError> {
+        let identifier = identifier.trim();
+        if let Some(cik) = parse_cik(identifier) {
+            return Ok(self.by_cik(&cik).cloned().unwrap_or(CompanyEntry { cik, name: String::new(), ticker: None, exchange: None }));
+        }
+        if let Some(entry) = self.by_ticker(identifier) {
+            return Ok(entry.clone());
+        }
+
+        let matches = self.search_name(identifier);
+        match matches.as_slice() {
+            [] => Err(EdgarError::CompanyNotFound(identifier.to_string())),
+            [(best, score), rest @ ..] => {
+                let tied: Vec<&CompanyEntry> = rest.iter().take_while(|(_, s)| s == score).map(|(e, _)| *e).collect();
+                if !tied.is_empty() {
+                    let candidates = std::iter::once(*best).chain(tied)
+                        .map(|e| format!("{} ({})", e.name, e.ticker.as_deref().unwrap_or(&e.cik)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    return Err(EdgarError::AmbiguousCompany { query: identifier.to_string(), candidates });
+                }
+                tracing::debug!("Matched company name '{}' to {} (score {:.2})", identifier, best.name, score);
+                Ok((*best).clone())
+            }
+        }
+    }
+}
+
+/// Brings a ticker into SEC's notation: upper case, share class separated by '-'.
+pub fn normalize_ticker(ticker: &str) -> String {
+    ticker.trim().to_ascii_uppercase().replace(['.', '/', ' '], "-")
+}
+
+/// Parses a raw CIK into its zero-padded 10-digit form.
+pub fn parse_cik(input: &str) -> Option<String> {
+    let digits = CIK_RE.captures(input.trim())?.get(1)?.as_str();
+    let cik: u64 = digits.parse().ok()?;
+    (cik > 0).then(|| format!("{:010}", cik))
+}
+
+/// Lower-case words of a company name, without punctuation and legal-form words.
+fn name_tokens(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .replace('&', " and ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !NAME_STOP_WORDS.contains(t))
+        .map(String::from)
+        .collect()
+}
+
+/// Share of query words found in the name (as words or word prefixes), weighted towards
+/// names without extra words, so "apple" prefers "Apple Inc." over "Apple Hospitality REIT".
+fn name_score(query: &[String], name: &[String]) -> f64 {
+    if name.is_empty() {
+        return 0.0;
+    }
+    if query == name {
+        return 1.0;
+    }
+    let matched: f64 = query.iter()
+        .map(|q| {
+            if name.contains(q) {
+                1.0
+            } else if q.len() >= 3 && name.iter().any(|n| n.starts_with(q.as_str())) {
+                0.8
+            } else {
+                0.0
+            }
+        })
+        .sum();
+    let coverage = matched / query.len() as f64;
+    let precision = matched / name.len().max(query.len()) as f64;
+    // Every query word must be found, at least as a prefix
+    if coverage < 0.8 {
+        return 0.0;
+    }
+    0.7 * coverage + 0.3 * precision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTORY_JSON: &str = r#"{"fields":["cik","name","ticker","exchange"],"data":[
+        [1067983,"BERKSHIRE HATHAWAY INC","BRK-B","NYSE"],
+        [1067983,"BERKSHIRE HATHAWAY INC","BRK-A","NYSE"],
+        [320193,"Apple Inc.","AAPL","Nasdaq"],
+        [1418121,"Apple Hospitality REIT, Inc.","APLE","NYSE"],
+        [1108134,"BERKSHIRE HILLS BANCORP INC","BHLB","NYSE"],
+        [1234567,"Test Widgets Corp","TSTC",null],
+        [2000001,"Acme Holdings Inc","ACMH","OTC"],
+        [2000002,"ACME HOLDINGS CORP","ACMC","OTC"]
+    ]}"#;
+
+    fn directory() -> CompanyDirectory {
+        CompanyDirectory::from_json(DIRECTORY_JSON.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_ticker_and_cik_lookup() {
+        let directory = directory();
+        assert_eq!(directory.len(), 8);
+        for ticker in ["BRK.B", "brk-b", "BRK/B"] {
+            assert_eq!(directory.by_ticker(ticker).unwrap().ticker.as_deref(), Some("BRK-B"), "{}", ticker);
+        }
+        assert_eq!(directory.by_ticker("AAPL").unwrap().exchange.as_deref(), Some("Nasdaq"));
+        assert_eq!(directory.by_ticker("TSTC").unwrap().exchange, None);
+
+        assert_eq!(directory.resolve("320193").unwrap().ticker.as_deref(), Some("AAPL"));
+        assert_eq!(directory.resolve("CIK0001067983").unwrap().ticker.as_deref(), Some("BRK-B"), "Primary share class first");
+        let unlisted = directory.resolve("0000999999").unwrap();
+        assert_eq!((unlisted.cik.as_str(), unlisted.ticker.as_deref()), ("0000999999", None));
+        assert_eq!(unlisted.output_ticker(), "999999");
+        assert_eq!(parse_cik("0"), None);
+    }
+
+    #[test]
+    fn test_cik_lookup_data() {
+        let names = CompanyDirectory::from_cik_lookup("APPLE COMPUTER INC:0000320193:\nAPPLE INC:0000320193:\nA:B CORP:0000000042:\nmalformed line\n");
+        assert_eq!(names.len(), 3);
+        assert_eq!(names.resolve("apple computer").unwrap().cik, "0000320193");
+        assert_eq!(names.resolve("a:b corp").unwrap().name, "A:B CORP");
+    }
+
+    #[test]
+    fn test_company_name_search() {
+        let directory = directory();
+        assert_eq!(directory.resolve("apple").unwrap().ticker.as_deref(), Some("AAPL"));
+        assert_eq!(directory.resolve("Apple Hospitality").unwrap().ticker.as_deref(), Some("APLE"));
+        assert_eq!(directory.resolve("Berkshire Hathaway").unwrap().cik, "0001067983");
+        assert_eq!(directory.resolve("test widgets corporation").unwrap().ticker.as_deref(), Some("TSTC"));
+        assert_eq!(directory.resolve("berkshire").unwrap().ticker.as_deref(), Some("BRK-B"), "Fewer extra words wins");
+        assert!(matches!(directory.resolve("acme holdings"), Err(EdgarError::AmbiguousCompany { .. })));
+        assert!(matches!(directory.resolve("Nonexistent Mining"), Err(EdgarError::CompanyNotFound(_))));
+    }
+}
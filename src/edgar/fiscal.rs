@@ -0,0 +1,181 @@
+// src/edgar/fiscal.rs
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// Slack allowed around the nominal fiscal year end for 52/53-week calendars,
+/// whose years end on a weekday near (not on) the `fiscalYearEnd` date.
+const FISCAL_YEAR_END_SLACK_DAYS: i64 = 14;
+
+/// Fiscal period a filing reports on, resolved from EDGAR's `reportDate` and `fiscalYearEnd`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiscalPeriod {
+    pub fiscal_year: u32,
+    /// 1-3 for quarterly reports
+    pub fiscal_quarter: Option<u8>,
+    /// Period end date (`YYYY-MM-DD`), when EDGAR lists one
+    pub period_end: Option<String>,
+}
+
+/// Parses a `YYYY-MM-DD` date into (year, month, day).
+pub fn parse_ymd(date: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = date.get(0..10)?.split('-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+/// Parses EDGAR's `fiscalYearEnd` (`MMDD`, e.g. "0930") into (month, day).
+pub fn parse_fiscal_year_end(mmdd: &str) -> Option<(u32, u32)> {
+    if mmdd.len() != 4 {
+        return None;
+    }
+    let month = mmdd[0..2].parse().ok()?;
+    let day = mmdd[2..4].parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((month, day))
+}
+
+/// Fiscal quarter (1-3) of a quarterly report, from its period end and the company's fiscal year end.
+///
+/// Counts months elapsed since the fiscal year end and rounds to whole quarters, so 52/53-week
+/// calendars whose quarters end a few days either side of the month end still land correctly
+/// (e.g. FYE 0930 with a quarter ending 2024-06-29 is Q3).
+pub fn fiscal_quarter(report_date: &str, fiscal_year_end: &str) -> Option<u8> {
+    let (_, month, day) = parse_ymd(report_date)?;
+    let (fye_month, fye_day) = parse_fiscal_year_end(fiscal_year_end)?;
+    let months_since_fye = ((month + 12 - fye_month) % 12) as f64 + (day as f64 - fye_day as f64) / 30.0;
+    let quarter = (months_since_fye / 3.0).round();
+    // Q4 is reported on the annual report, so a 10-Q is always Q1-Q3
+    (1.0..=3.0).contains(&quarter).then_some(quarter as u8)
+}
+
+/// The nominal fiscal year end date in a calendar year; Feb 29 falls back to Feb 28 outside leap years.
+fn fiscal_year_end_date(year: i32, (month, day): (u32, u32)) -> Option<NaiveDate> {
+    (0..3).find_map(|back| NaiveDate::from_ymd_opt(year, month, day.checked_sub(back)?))
+}
+
+/// Fiscal year containing `date`, named after the calendar year in which that fiscal year ends.
+///
+/// Fiscal years ending in the first week of January are 52/53-week years that "belong" to the
+/// previous December, so they take the previous year's name (e.g. FYE 0102 ending 2021-01-02 is 2020).
+/// Retail years ending late January keep the year they end in (FYE 0131 ending 2024-01-31 is 2024).
+pub fn fiscal_year_of(date: &str, fiscal_year_end: &str) -> Option<u32> {
+    let (year, month, day) = parse_ymd(date)?;
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    let fye = parse_fiscal_year_end(fiscal_year_end)?;
+    let slack = Duration::days(FISCAL_YEAR_END_SLACK_DAYS);
+    let end_year = (date.year() - 1..=date.year() + 1)
+        .find(|&candidate| fiscal_year_end_date(candidate, fye).is_some_and(|end| date <= end + slack))?;
+    fiscal_year_name(end_year, fye)
+}
+
+/// Fiscal year whose nominal end is the latest on or before `date`, without slack: the year an
+/// annual report filed on `date` covers, even when filed within days of the year end.
+fn last_ended_fiscal_year(date: &str, fiscal_year_end: &str) -> Option<u32> {
+    let (year, month, day) = parse_ymd(date)?;
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    let fye = parse_fiscal_year_end(fiscal_year_end)?;
+    let end_year = (date.year() - 1..=date.year()).rev()
+        .find(|&candidate| fiscal_year_end_date(candidate, fye).is_some_and(|end| end <= date))?;
+    fiscal_year_name(end_year, fye)
+}
+
+/// Name of the fiscal year ending in calendar year `end_year` (see `fiscal_year_of`).
+fn fiscal_year_name(end_year: i32, (month, day): (u32, u32)) -> Option<u32> {
+    let named_year = if month == 1 && day <= 7 { end_year - 1 } else { end_year };
+    u32::try_from(named_year).ok()
+}
+
+/// Resolves the fiscal period of a filing.
+///
+/// The period end (`reportDate`) decides the fiscal year when present. Without it the filing date
+/// is used instead: a quarterly report is filed within the fiscal year it covers, an annual report
+/// after it ends. Without a usable `fiscalYearEnd`, the calendar year of the period end (or, failing
+/// that, of the filing date) is used.
+pub fn resolve_fiscal_period(report_date: Option<&str>, filing_date: &str, fiscal_year_end: &str, quarterly: bool)
+    -> Option<FiscalPeriod>
+{
+    let report_date = report_date.filter(|d| parse_ymd(d).is_some());
+    let fiscal_year = match report_date {
+        Some(period_end) => fiscal_year_of(period_end, fiscal_year_end),
+        None if quarterly => fiscal_year_of(filing_date, fiscal_year_end),
+        None => last_ended_fiscal_year(filing_date, fiscal_year_end),
+    }
+    .or_else(|| parse_ymd(report_date.unwrap_or(filing_date)).map(|(year, _, _)| year))?;
+    let fiscal_quarter = if quarterly {
+        report_date.and_then(|date| fiscal_quarter(date, fiscal_year_end))
+    } else {
+        None
+    };
+    Some(FiscalPeriod { fiscal_year, fiscal_quarter, period_end: report_date.map(str::to_string) })
+}
+
+/// Filing-date years that can hold filings for the fiscal years `start_year..=end_year`.
+///
+/// The first 10-Q of fiscal year N may be filed in calendar year N-1 (when its first quarter ends
+/// before December), and the annual report is often filed in N+1. Used to decide which older
+/// submissions pages (bucketed by filing date) need fetching.
+pub fn filing_year_window(start_year: Option<u32>, end_year: Option<u32>, fiscal_year_end: &str)
+    -> (Option<u32>, Option<u32>)
+{
+    let first_quarter_ends_in_prior_year = match parse_fiscal_year_end(fiscal_year_end) {
+        Some((month, _)) => month + 3 <= 12,
+        None => true,
+    };
+    let start = start_year.map(|year| if first_quarter_ends_in_prior_year { year.saturating_sub(1) } else { year });
+    (start, end_year.map(|year| year + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fiscal_quarter() {
+        // Calendar fiscal year
+        assert_eq!(fiscal_quarter("2023-03-31", "1231"), Some(1));
+        assert_eq!(fiscal_quarter("2023-09-30", "1231"), Some(3));
+        // September fiscal year end with 52/53-week quarters
+        assert_eq!(fiscal_quarter("2023-12-30", "0930"), Some(1));
+        assert_eq!(fiscal_quarter("2024-03-30", "0930"), Some(2));
+        assert_eq!(fiscal_quarter("2024-06-29", "0930"), Some(3));
+        // Retail calendar ending late January/early February
+        assert_eq!(fiscal_quarter("2023-04-29", "0128"), Some(1));
+        // Fiscal year end itself is not a 10-Q quarter
+        assert_eq!(fiscal_quarter("2023-12-31", "1231"), None);
+        assert_eq!(fiscal_quarter("", "1231"), None);
+        assert_eq!(fiscal_quarter("2023-03-31", ""), None);
+    }
+
+    #[test]
+    fn test_fiscal_year_resolution() {
+        // Calendar year filed the following February
+        let period = resolve_fiscal_period(Some("2023-12-31"), "2024-02-15", "1231", false).unwrap();
+        assert_eq!(period, FiscalPeriod { fiscal_year: 2023, fiscal_quarter: None, period_end: Some("2023-12-31".to_string()) });
+        // September year end: Q1 ends in the prior calendar year
+        let q1 = resolve_fiscal_period(Some("2022-12-31"), "2023-02-03", "0930", true).unwrap();
+        assert_eq!((q1.fiscal_year, q1.fiscal_quarter), (2023, Some(1)));
+        // 52/53-week years ending a few days before/after the nominal date
+        assert_eq!(fiscal_year_of("2022-09-24", "0930"), Some(2022));
+        assert_eq!(fiscal_year_of("2022-01-01", "1230"), Some(2021));
+        assert_eq!(fiscal_year_of("2021-01-02", "0102"), Some(2020));
+        // Retail year ending in late January
+        assert_eq!(fiscal_year_of("2024-01-31", "0131"), Some(2024));
+        assert_eq!(fiscal_year_of("2023-04-29", "0131"), Some(2024));
+        // Fallbacks: no report date, then no fiscal year end
+        assert_eq!(resolve_fiscal_period(None, "2024-02-15", "1231", false).unwrap().fiscal_year, 2023);
+        // Filed within the 52/53-week slack after the year end, still for the year just ended
+        assert_eq!(resolve_fiscal_period(None, "2024-01-10", "1231", false).unwrap().fiscal_year, 2023);
+        assert_eq!(resolve_fiscal_period(None, "2023-11-20", "0930", false).unwrap().fiscal_year, 2023);
+        assert_eq!(resolve_fiscal_period(None, "2021-03-01", "0102", false).unwrap().fiscal_year, 2020);
+        assert_eq!(resolve_fiscal_period(Some(""), "2024-05-02", "1231", true).unwrap().fiscal_year, 2024);
+        assert_eq!(resolve_fiscal_period(Some("2023-12-31"), "2024-02-15", "", false).unwrap().fiscal_year, 2023);
+        assert_eq!(resolve_fiscal_period(None, "", "1231", false), None);
+    }
+
+    #[test]
+    fn test_filing_year_window() {
+        assert_eq!(filing_year_window(Some(2023), Some(2023), "1231"), (Some(2023), Some(2024)));
+        assert_eq!(filing_year_window(Some(2023), None, "0630"), (Some(2022), None));
+        assert_eq!(filing_year_window(None, Some(2020), ""), (None, Some(2021)));
+    }
+}
@@ -0,0 +1,318 @@
+// src/edgar/forms.rs
+use crate::edgar::models::FilingInfo;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Suffix EDGAR appends to the form type of an amended filing (e.g. "10-K/A").
+const AMENDMENT_SUFFIX: &str = "/A";
+
+/// Annual report forms treated as "a 10-K": the modern form, the pre-2003 variant with the
+/// Item 405 box checked, transition reports, and the pre-2009 small business forms.
+pub const ANNUAL_REPORT_FORMS: &[&str] = &["10-K", "10-K405", "10-KT", "10-KT405", "10-KSB", "10-KSB40", "10-KTSB"];
+
+/// Quarterly report forms: the 10-Q, its transition variant and the pre-2009 small business form.
+pub const QUARTERLY_REPORT_FORMS: &[&str] = &["10-Q", "10-QT", "10-QSB", "10-QTSB"];
+
+/// Transition reports, filed for the short period after a change of fiscal year end. They can
+/// cover the same fiscal year as the family's regular report.
+pub const TRANSITION_REPORT_FORMS: &[&str] = &["10-KT", "10-KT405", "10-KTSB", "10-QT", "10-QTSB"];
+
+/// Annual reports of foreign private issuers.
+pub const FOREIGN_ANNUAL_REPORT_FORMS: &[&str] = &["20-F"];
+
+/// Forms the section models can't extract: the Canadian (MJDS) 40-F files its financial
+/// statements and MD&A as exhibits rather than in the main document.
+pub const UNSUPPORTED_FORMS: &[&str] = &["40-F"];
+
+/// Rejects forms (amendment suffix ignored) listed in `UNSUPPORTED_FORMS`.
+pub fn check_supported(form: &str) -> Result<(), String> {
+    let base_form = FormMatch::parse(form).base_form;
+    if UNSUPPORTED_FORMS.contains(&base_form.as_str()) {
+        return Err(format!("{} reports are not supported: their financial statements are filed as exhibits", base_form));
+    }
+    Ok(())
+}
+
+/// Kind of periodic report being processed; selects the default forms and the section model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportType {
+    /// 10-K family
+    #[default]
+    Annual,
+    /// 10-Q family
+    Quarterly,
+    /// 20-F (foreign private issuers)
+    ForeignAnnual,
+}
+
+impl ReportType {
+    pub fn default_forms(&self) -> &'static [&'static str] {
+        match self {
+            Self::Annual => ANNUAL_REPORT_FORMS,
+            Self::Quarterly => QUARTERLY_REPORT_FORMS,
+            Self::ForeignAnnual => FOREIGN_ANNUAL_REPORT_FORMS,
+        }
+    }
+
+    /// The report type whose default forms include `form` (amendment suffix ignored).
+    pub fn for_form(form: &str) -> Option<Self> {
+        let base_form = FormMatch::parse(form).base_form;
+        [Self::Annual, Self::Quarterly, Self::ForeignAnnual].into_iter()
+            .find(|report_type| report_type.default_forms().contains(&base_form.as_str()))
+    }
+}
+
+impl FromStr for ReportType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check_supported(s)?;
+        match s.to_ascii_lowercase().as_str() {
+            "annual" | "10-k" => Ok(Self::Annual),
+            "quarterly" | "10-q" => Ok(Self::Quarterly),
+            "foreign" | "20-f" => Ok(Self::ForeignAnnual),
+            other => Err(format!("unknown report type '{}' (expected annual, quarterly or foreign)", other)),
+        }
+    }
+}
+
+impl fmt::Display for ReportType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Annual => "annual",
+            Self::Quarterly => "quarterly",
+            Self::ForeignAnnual => "foreign",
+        })
+    }
+}
+
+/// How amended filings (`<form>/A`) relate to the originals they amend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmendmentPolicy {
+    /// Keep originals and amendments as separate filings
+    KeepAll,
+    /// For each reporting period keep only the most recently filed document (amendment or original).
+    /// Note that many 10-K/A filings only amend Part III and contain no financial statements.
+    PreferLatest,
+    /// Drop amendments entirely
+    #[default]
+    OriginalsOnly,
+}
+
+impl FromStr for AmendmentPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "keep-all" => Ok(Self::KeepAll),
+            "prefer-latest" => Ok(Self::PreferLatest),
+            "originals-only" => Ok(Self::OriginalsOnly),
+            other => Err(format!("unknown amendment policy '{}' (expected keep-all, prefer-latest or originals-only)", other)),
+        }
+    }
+}
+
+impl fmt::Display for AmendmentPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::KeepAll => "keep-all",
+            Self::PreferLatest => "prefer-latest",
+            Self::OriginalsOnly => "originals-only",
+        })
+    }
+}
+
+/// Result of matching an EDGAR form type against a `FormFilter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormMatch {
+    /// Form type without the amendment suffix (e.g. "10-K" for "10-K/A")
+    pub base_form: String,
+    pub is_amendment: bool,
+}
+
+impl FormMatch {
+    /// Splits a form type into its base form and amendment flag ("10-k/a" -> "10-K", true).
+    pub fn parse(form: &str) -> Self {
+        let form = form.trim().to_ascii_uppercase();
+        match form.strip_suffix(AMENDMENT_SUFFIX) {
+            Some(base) => Self { base_form: base.to_string(), is_amendment: true },
+            None => Self { base_form: form, is_amendment: false },
+        }
+    }
+
+    /// Whether the base form is a transition report (see `TRANSITION_REPORT_FORMS`).
+    pub fn is_transition(&self) -> bool {
+        TRANSITION_REPORT_FORMS.contains(&self.base_form.as_str())
+    }
+}
+
+/// Selects which EDGAR form types a filing search returns.
+#[derive(Debug, Clone)]
+pub struct FormFilter {
+    /// Base (unamended) form types, upper-case
+    forms: Vec<String>,
+    pub amendments: AmendmentPolicy,
+}
+
+impl FormFilter {
+    /// Filter for the default forms of a report type.
+    pub fn for_report_type(report_type: ReportType, amendments: AmendmentPolicy) -> Self {
+        Self::from_forms(report_type.default_forms(), amendments)
+    }
+
+    /// Filter for an explicit list of base forms (amendment suffixes in the list are ignored).
+    pub fn from_forms<S: AsRef<str>>(forms: &[S], amendments: AmendmentPolicy) -> Self {
+        let mut normalized: Vec<String> = Vec::new();
+        for form in forms {
+            let form = form.as_ref().trim().to_ascii_uppercase();
+            let form = form.strip_suffix(AMENDMENT_SUFFIX).unwrap_or(&form).to_string();
+            if !form.is_empty() && !normalized.contains(&form) {
+                normalized.push(form);
+            }
+        }
+        Self { forms: normalized, amendments }
+    }
+
+    pub fn forms(&self) -> &[String] {
+        &self.forms
+    }
+
+    /// Matches a form type from the submissions JSON. Amendments are rejected up front
+    /// under `AmendmentPolicy::OriginalsOnly`.
+    pub fn matches(&self, form: &str) -> Option<FormMatch> {
+        let form_match = FormMatch::parse(form);
+        if form_match.is_amendment && self.amendments == AmendmentPolicy::OriginalsOnly {
+            return None;
+        }
+        self.forms.contains(&form_match.base_form).then_some(form_match)
+    }
+
+    /// Applies the amendment policy to a list of matched filings.
+    /// Under `PreferLatest`, filings are grouped by company and reporting period
+    /// (report date, or year when unknown) and only the latest filed one survives.
+    pub fn apply_amendment_policy(&self, filings: Vec<FilingInfo>) -> Vec<FilingInfo> {
+        match self.amendments {
+            AmendmentPolicy::KeepAll => filings,
+            AmendmentPolicy::OriginalsOnly => filings.into_iter().filter(|f| !f.is_amendment).collect(),
+            AmendmentPolicy::PreferLatest => {
+                // An amendment may be filed in a later year, so the year is only a fallback key
+                let period_key = |f: &FilingInfo| match f.report_date.as_deref().filter(|d| !d.is_empty()) {
+                    Some(report_date) => (f.cik.clone(), Some(report_date.to_string()), None),
+                    None => (f.cik.clone(), None, f.year),
+                };
+                let mut latest: HashMap<_, FilingInfo> = HashMap::new();
+                for filing in filings.iter() {
+                    let entry = latest.entry(period_key(filing)).or_insert_with(|| filing.clone());
+                    // Filing dates are ISO formatted, so string order is date order
+                    if (filing.filing_date.as_str(), &filing.accession_number) > (entry.filing_date.as_str(), &entry.accession_number) {
+                        *entry = filing.clone();
+                    }
+                }
+                // Preserve the input order
+                filings.into_iter()
+                    .filter(|f| latest.get(&period_key(f)).is_some_and(|l| l.accession_number == f.accession_number))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annual_reports(amendments: AmendmentPolicy) -> FormFilter {
+        FormFilter::for_report_type(ReportType::Annual, amendments)
+    }
+
+    fn filing(acc: &str, form: &str, filed: &str, report: &str) -> FilingInfo {
+        FilingInfo {
+            accession_number: acc.to_string(),
+            filing_date: filed.to_string(),
+            form_type: form.to_string(),
+            ticker: "TSTC".to_string(),
+            company_name: "Test Widgets Corp".to_string(),
+            cik: "0001234567".to_string(),
+            primary_doc: "doc.htm".to_string(),
+            year: Some(2023),
+            is_amendment: form.ends_with("/A"),
+            report_date: Some(report.to_string()),
+            fiscal_quarter: None,
+            documents: Vec::new(),
+            exchange: None,
+        }
+    }
+
+    #[test]
+    fn test_annual_report_family_matching() {
+        let filter = annual_reports(AmendmentPolicy::KeepAll);
+        assert_eq!(filter.matches("10-K405"), Some(FormMatch { base_form: "10-K405".to_string(), is_amendment: false }));
+        assert_eq!(filter.matches("10-KSB/A"), Some(FormMatch { base_form: "10-KSB".to_string(), is_amendment: true }));
+        assert!(filter.matches("10-Q").is_none());
+        assert!(filter.matches("8-K").is_none());
+
+        let originals = annual_reports(AmendmentPolicy::OriginalsOnly);
+        assert!(originals.matches("10-K/A").is_none());
+        assert!(originals.matches("10-KT").is_some());
+
+        let quarterly = FormFilter::for_report_type(ReportType::Quarterly, AmendmentPolicy::OriginalsOnly);
+        assert!(quarterly.matches("10-Q").is_some());
+        assert!(quarterly.matches("10-K").is_none());
+
+        let foreign = FormFilter::for_report_type(ReportType::ForeignAnnual, AmendmentPolicy::KeepAll);
+        assert!(foreign.matches("20-F").is_some());
+        assert_eq!(foreign.matches("20-F/A"), Some(FormMatch { base_form: "20-F".to_string(), is_amendment: true }));
+        // 40-F financial statements are exhibits the 20-F model can't extract
+        assert!(foreign.matches("40-F").is_none());
+        assert!(foreign.matches("6-K").is_none());
+
+        let custom = FormFilter::from_forms(&["10-kt", "10-KT/A"], AmendmentPolicy::KeepAll);
+        assert_eq!(custom.forms(), ["10-KT"]);
+        assert!(custom.matches("10-K").is_none());
+    }
+
+    #[test]
+    fn test_prefer_latest_replaces_original_with_amendment() {
+        let filings = vec![
+            filing("0001234567-24-000010", "10-K", "2024-02-15", "2023-12-31"),
+            filing("0001234567-24-000031", "10-K/A", "2024-04-29", "2023-12-31"),
+            filing("0001234567-23-000012", "10-K", "2023-02-14", "2022-12-31"),
+        ];
+
+        let latest = annual_reports(AmendmentPolicy::PreferLatest).apply_amendment_policy(filings.clone());
+        let kept: Vec<&str> = latest.iter().map(|f| f.accession_number.as_str()).collect();
+        assert_eq!(kept, ["0001234567-24-000031", "0001234567-23-000012"]);
+
+        assert_eq!(annual_reports(AmendmentPolicy::KeepAll).apply_amendment_policy(filings.clone()).len(), 3);
+        assert_eq!(annual_reports(AmendmentPolicy::OriginalsOnly).apply_amendment_policy(filings).len(), 2);
+    }
+
+    #[test]
+    fn test_prefer_latest_groups_amendment_filed_next_year() {
+        let filings = vec![
+            filing("0001234567-23-000040", "10-K", "2023-12-20", "2023-09-30"),
+            FilingInfo { year: Some(2024), ..filing("0001234567-24-000002", "10-K/A", "2024-01-26", "2023-09-30") },
+        ];
+        let latest = annual_reports(AmendmentPolicy::PreferLatest).apply_amendment_policy(filings);
+        let kept: Vec<&str> = latest.iter().map(|f| f.accession_number.as_str()).collect();
+        assert_eq!(kept, ["0001234567-24-000002"]);
+    }
+
+    #[test]
+    fn test_policy_parsing() {
+        assert_eq!("prefer-latest".parse::<AmendmentPolicy>(), Ok(AmendmentPolicy::PreferLatest));
+        assert_eq!(AmendmentPolicy::KeepAll.to_string(), "keep-all");
+        assert!("latest".parse::<AmendmentPolicy>().is_err());
+        assert_eq!("10-Q".parse::<ReportType>(), Ok(ReportType::Quarterly));
+        assert_eq!(ReportType::Annual.to_string(), "annual");
+        assert_eq!("20-F".parse::<ReportType>(), Ok(ReportType::ForeignAnnual));
+        assert_eq!(ReportType::for_form("10-Q/A"), Some(ReportType::Quarterly));
+        assert_eq!(ReportType::for_form("8-K"), None);
+        assert_eq!(ReportType::for_form("40-F"), None);
+        let error = "40-f".parse::<ReportType>().unwrap_err();
+        assert!(error.contains("40-F reports are not supported"), "{}", error);
+        assert!(check_supported("40-F/A").is_err());
+        assert!(check_supported("20-F").is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+// src/edgar/mock_server.rs
+//! Minimal local HTTP server standing in for SEC in client tests.
+//! Point `EdgarConfig` base URLs at `MockServer::url()` to exercise the real client code.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A request as seen by the mock server.
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub path: String,
+    /// Header names are lower-cased
+    pub headers: HashMap<String, String>,
+}
+
+/// The response a handler wants sent back.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self { status: 200, headers: Vec::new(), body: body.into() }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self { status, headers: Vec::new(), body: Vec::new() }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+type Handler = dyn Fn(&MockRequest) -> MockResponse + Send + Sync;
+
+pub struct MockServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Starts a server on an ephemeral local port; `handler` answers every request.
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let handler = Arc::clone(&handler);
+                let recorded = Arc::clone(&recorded);
+                tokio::spawn(async move {
+                    let Some(request) = read_request(&mut stream).await else { return };
+                    recorded.lock().unwrap().push(request.clone());
+                    let response = handler(&request);
+                    let _ = stream.write_all(&encode_response(&response)).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    /// Serves files from a fixtures directory laid out as `<root>/<path>`,
+    /// answering 404 for anything missing.
+    pub async fn serve_dir(root: &str) -> Self {
+        let root = std::path::PathBuf::from(root);
+        Self::start(move |req| match std::fs::read(root.join(req.path.trim_start_matches('/'))) {
+            Ok(body) => MockResponse::ok(body),
+            Err(_) => MockResponse::status(404),
+        }).await
+    }
+
+    pub fn url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<MockRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let head = String::from_utf8_lossy(&buf);
+    let mut lines = head.split("\r\n");
+    let path = lines.next()?.split_whitespace().nth(1)?.to_string();
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    Some(MockRequest { path, headers })
+}
+
+fn encode_response(response: &MockResponse) -> Vec<u8> {
+    let mut out = format!("HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+                          response.status, response.body.len());
+    for (name, value) in &response.headers {
+        out.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    out.push_str("\r\n");
+    let mut bytes = out.into_bytes();
+    bytes.extend_from_slice(&response.body);
+    bytes
+}
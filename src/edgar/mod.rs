@@ -1,3 +1,14 @@
 // src/edgar/mod.rs
+pub mod cache;
 pub mod client;
-pub mod models;
\ No newline at end of file
+pub mod config;
+pub mod directory;
+pub mod fiscal;
+pub mod forms;
+#[cfg(test)]
+pub mod mock_server;
+pub mod models;
+pub mod offline;
+pub mod rate_limit;
+pub mod retry;
+pub mod sgml;
\ No newline at end of file
@@ -1,5 +1,6 @@
 // src/edgar/models.rs
 #![allow(dead_code, non_snake_case)]
+use crate::edgar::sgml::SubmissionHeader;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -31,6 +32,18 @@ pub struct CompanySubmission {
     pub filings: Filings,
 }
 
+impl CompanySubmission {
+    /// Company name in effect on `date` (`YYYY-MM-DD`): a former name whose `from`/`to`
+    /// range covers the date, else the current name.
+    pub fn name_on(&self, date: &str) -> &str {
+        let day = |timestamp: &str| timestamp.get(0..10).unwrap_or(timestamp).to_string();
+        self.formerNames.iter()
+            .find(|former| day(&former.from).as_str() <= date && date <= day(&former.to).as_str())
+            .map(|former| former.name.as_str())
+            .unwrap_or(&self.name)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Address {
     pub street1: Option<String>,
@@ -51,6 +64,8 @@ pub struct FormerName {
 #[derive(Debug, Deserialize)]
 pub struct Filings {
     pub recent: FilingsList,
+    /// Older filings beyond the ~1000 in `recent`, paged into separate JSON files
+    #[serde(default)]
     pub files: Vec<FilingFile>,
 }
 
@@ -62,7 +77,21 @@ pub struct FilingFile {
     pub filingTo: String,
 }
 
-#[derive(Debug, Deserialize)]
+impl FilingFile {
+    /// Whether this page holds filings made within the (inclusive) year range.
+    /// Dates are `YYYY-MM-DD`; unparseable dates count as overlapping so we never skip data.
+    pub fn overlaps_years(&self, start_year: Option<u32>, end_year: Option<u32>) -> bool {
+        let year_of = |date: &str| date.get(0..4).and_then(|y| y.parse::<u32>().ok());
+        let ends_before_start = matches!((year_of(&self.filingTo), start_year), (Some(to), Some(start)) if to < start);
+        let starts_after_end = matches!((year_of(&self.filingFrom), end_year), (Some(from), Some(end)) if from > end);
+        !ends_before_start && !starts_after_end
+    }
+}
+
+/// Column-oriented filing list: index `i` across all vectors describes one filing.
+/// Used both for `filings.recent` and for the older `CIK##########-submissions-###.json` pages.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct FilingsList {
     pub accessionNumber: Vec<String>,
     pub filingDate: Vec<String>,
@@ -80,6 +109,96 @@ pub struct FilingsList {
     pub primaryDocDescription: Vec<String>,
 }
 
+/// Directory listing of a filing folder (`{folder}/index.json`)
+#[derive(Debug, Deserialize)]
+pub struct FolderIndex {
+    pub directory: FolderDirectory,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FolderDirectory {
+    /// Folder path, e.g. `/Archives/edgar/data/320193/000032019323000106`
+    pub name: String,
+    #[serde(default)]
+    pub item: Vec<FolderItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FolderItem {
+    pub name: String,
+    /// Icon type ("text.gif", "folder.gif"), not the document type
+    #[serde(rename = "type", default)]
+    pub item_type: String,
+    /// Size in bytes as a string; empty for generated index pages
+    #[serde(default)]
+    pub size: String,
+    #[serde(rename = "last-modified", default)]
+    pub last_modified: String,
+}
+
+impl FolderDirectory {
+    /// CIK taken from the folder path, zero-padded to 10 digits
+    pub fn cik(&self) -> Option<String> {
+        let cik: u64 = self.name.trim_end_matches('/').rsplit('/').nth(1)?.parse().ok()?;
+        Some(format!("{:010}", cik))
+    }
+
+    pub fn contains(&self, file_name: &str) -> bool {
+        self.item.iter().any(|item| item.name == file_name)
+    }
+
+    /// Combines the folder listing with the SGML header's document list into the filing index.
+    /// Submitted documents come first, in sequence order; files EDGAR generated itself
+    /// (R-file renderings, index pages, the full `.txt` submission) follow without type or sequence.
+    pub fn documents(&self, header: &SubmissionHeader) -> Vec<FilingDocument> {
+        let size_of = |name: &str| self.item.iter().find(|item| item.name == name).and_then(|item| item.size.parse().ok());
+        let mut submitted: Vec<FilingDocument> = header.documents.iter()
+            .filter(|doc| !doc.filename.is_empty())
+            .map(|doc| FilingDocument {
+                sequence: doc.sequence,
+                filename: doc.filename.clone(),
+                doc_type: Some(doc.doc_type.clone()).filter(|t| !t.is_empty()),
+                description: doc.description.clone(),
+                size: size_of(&doc.filename),
+            })
+            .collect();
+        submitted.sort_by_key(|doc| doc.sequence.unwrap_or(u32::MAX));
+
+        let generated: Vec<FilingDocument> = self.item.iter()
+            .filter(|item| item.item_type != "folder.gif" && !submitted.iter().any(|doc| doc.filename == item.name))
+            .map(|item| FilingDocument {
+                filename: item.name.clone(),
+                size: item.size.parse().ok(),
+                ..FilingDocument::default()
+            })
+            .collect();
+        submitted.into_iter().chain(generated).collect()
+    }
+}
+
+/// One file in a filing folder (filing index entry).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilingDocument {
+    /// Position in the submission (1 = primary document); `None` for files generated by EDGAR
+    pub sequence: Option<u32>,
+    pub filename: String,
+    /// EDGAR document type, e.g. "10-K", "EX-21.1", "EX-101.INS", "GRAPHIC"
+    pub doc_type: Option<String>,
+    pub description: Option<String>,
+    /// Size in bytes, when the folder listing reports one
+    pub size: Option<u64>,
+}
+
+/// Normalizes an accession number to the dashed `##########-YY-######` form.
+/// Accepts the 18 digits with or without dashes.
+pub fn normalize_accession_number(input: &str) -> Option<String> {
+    let digits: String = input.trim().chars().filter(|c| *c != '-').collect();
+    if digits.len() != 18 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("{}-{}-{}", &digits[0..10], &digits[10..12], &digits[12..18]))
+}
+
 /// Simple struct representing a specific filing we want to process
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilingInfo {
@@ -91,15 +210,113 @@ pub struct FilingInfo {
     pub cik: String,
     pub primary_doc: String,
     pub year: Option<u32>, // Fiscal year of the report
+    #[serde(default)]
+    pub is_amendment: bool, // True for "<form>/A" filings
+    #[serde(default)]
+    pub report_date: Option<String>, // Period end date (YYYY-MM-DD), if EDGAR lists one
+    #[serde(default)]
+    pub fiscal_quarter: Option<u8>, // 1-3 for quarterly reports, None for annual reports
+    #[serde(default)]
+    pub documents: Vec<FilingDocument>, // Filing index; empty until loaded (see `EdgarClient::load_documents`)
+    #[serde(default)]
+    pub exchange: Option<String>, // Listing exchange from the ticker directory, if listed
 }
 
 impl FilingInfo {
-    /// Constructs the URL to access the primary document of this filing
-    pub fn primary_doc_url(&self) -> String {
+    /// Constructs the URL of this filing's folder in the archives
+    /// (`{archives_base_url}/edgar/data/{cik}/{accession without dashes}`)
+    pub fn folder_url(&self, archives_base_url: &str) -> String {
         let acc_no_dashes = self.accession_number.replace("-", "");
-        format!(
-            "https://www.sec.gov/Archives/edgar/data/{}/{}/{}",
-            self.cik, acc_no_dashes, self.primary_doc
-        )
+        format!("{}/edgar/data/{}/{}", archives_base_url, self.cik, acc_no_dashes)
+    }
+
+    /// Constructs the URL to access the primary document of this filing
+    pub fn primary_doc_url(&self, archives_base_url: &str) -> String {
+        self.document_url(archives_base_url, &self.primary_doc)
+    }
+
+    /// Constructs the URL of the full submission text file (`<accession>.txt`), which holds every
+    /// document of the filing in SGML envelopes
+    pub fn full_submission_url(&self, archives_base_url: &str) -> String {
+        self.document_url(archives_base_url, &format!("{}.txt", self.accession_number))
     }
-}
\ No newline at end of file
+
+    /// Constructs the URL of any file in this filing's folder
+    pub fn document_url(&self, archives_base_url: &str, filename: &str) -> String {
+        format!("{}/{}", self.folder_url(archives_base_url), filename)
+    }
+
+    /// First document of the given EDGAR type (case-insensitive), e.g. "EX-101.INS" for the XBRL instance.
+    /// Requires the filing index to be loaded.
+    pub fn document_of_type(&self, doc_type: &str) -> Option<&FilingDocument> {
+        self.documents.iter().find(|doc| doc.doc_type.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(doc_type)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filing_file_year_overlap() {
+        let page = FilingFile {
+            name: "CIK0000320193-submissions-001.json".to_string(),
+            filingCount: 1200,
+            filingFrom: "1994-01-26".to_string(),
+            filingTo: "2014-06-30".to_string(),
+        };
+        assert!(page.overlaps_years(None, None));
+        assert!(page.overlaps_years(Some(2010), None));
+        assert!(page.overlaps_years(Some(2014), Some(2020)));
+        assert!(!page.overlaps_years(Some(2015), None));
+        assert!(!page.overlaps_years(None, Some(1993)));
+    }
+
+    #[test]
+    fn test_filing_index_merges_listing_and_header() {
+        let directory: FolderDirectory = serde_json::from_str(r#"{
+            "name": "/Archives/edgar/data/320193/000032019323000106",
+            "item": [
+                {"name": "0000320193-23-000106-index.htm", "type": "text.gif", "size": ""},
+                {"name": "R2.htm", "type": "text.gif", "size": "3904"},
+                {"name": "aapl-20230930.htm", "type": "text.gif", "size": "1520340"},
+                {"name": "ex21.htm", "type": "text.gif", "size": "1630"}
+            ]
+        }"#).unwrap();
+        let header = SubmissionHeader {
+            form_type: "10-K".to_string(),
+            documents: vec![
+                crate::edgar::sgml::SubmissionDocument { doc_type: "EX-21.1".to_string(), sequence: Some(2), filename: "ex21.htm".to_string(), ..Default::default() },
+                crate::edgar::sgml::SubmissionDocument { doc_type: "10-K".to_string(), sequence: Some(1), filename: "aapl-20230930.htm".to_string(), description: Some("10-K".to_string()), text: None },
+            ],
+            ..SubmissionHeader::default()
+        };
+
+        assert_eq!(directory.cik().as_deref(), Some("0000320193"));
+        let documents = directory.documents(&header);
+        let names: Vec<&str> = documents.iter().map(|d| d.filename.as_str()).collect();
+        assert_eq!(names, ["aapl-20230930.htm", "ex21.htm", "0000320193-23-000106-index.htm", "R2.htm"]);
+        assert_eq!(documents[0].size, Some(1_520_340));
+        assert_eq!(documents[1].doc_type.as_deref(), Some("EX-21.1"));
+        assert_eq!((documents[3].sequence, documents[3].doc_type.as_deref(), documents[3].size), (None, None, Some(3904)));
+        assert_eq!(documents[2].size, None);
+    }
+
+    #[test]
+    fn test_name_in_effect_on_date() {
+        let json = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/edgar/data.sec.gov/submissions/CIK0001234567.json")).unwrap();
+        let submission: CompanySubmission = serde_json::from_str(&json).unwrap();
+        assert_eq!(submission.name_on("2001-03-20"), "Test Gadgets Inc");
+        assert_eq!(submission.name_on("2015-06-30"), "Test Gadgets Inc");
+        assert_eq!(submission.name_on("2022-02-11"), "Test Widgets Corp");
+        assert_eq!(submission.name_on("1999-01-01"), "Test Widgets Corp");
+    }
+
+    #[test]
+    fn test_accession_number_normalization() {
+        assert_eq!(normalize_accession_number("000032019323000106").as_deref(), Some("0000320193-23-000106"));
+        assert_eq!(normalize_accession_number(" 0000320193-23-000106 ").as_deref(), Some("0000320193-23-000106"));
+        assert_eq!(normalize_accession_number("0000320193-23-00010"), None);
+        assert_eq!(normalize_accession_number("aapl-20230930"), None);
+    }
+}
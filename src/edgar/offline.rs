@@ -0,0 +1,76 @@
+// src/edgar/offline.rs
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Directory of recorded EDGAR responses used in `--offline` mode.
+///
+/// A URL maps to `<root>/<host>/<path>`, mirroring the SEC site layout, e.g.
+/// `https://data.sec.gov/submissions/CIK0000320193.json` is read from
+/// `<root>/data.sec.gov/submissions/CIK0000320193.json`. A query string, if any,
+/// is appended to the file name with `?` replaced by `_`.
+#[derive(Debug, Clone)]
+pub struct FixtureStore {
+    root: PathBuf,
+}
+
+impl FixtureStore {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+
+    /// Maps a URL to its fixture file. Returns `None` for URLs that can't be mapped safely.
+    pub fn path_for(&self, url: &str) -> Option<PathBuf> {
+        let without_scheme = url.split_once("://").map(|(_, rest)| rest)?;
+        let relative = without_scheme.replace('?', "_");
+        let relative = Path::new(&relative);
+        // Never let a URL escape the fixtures directory
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return None;
+        }
+        Some(self.root.join(relative))
+    }
+
+    /// Reads the recorded body for a URL, if present.
+    pub fn load(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.path_for(url)?;
+        match fs::read(&path) {
+            Ok(body) => {
+                tracing::debug!("Serving {} from fixture {}", url, path.display());
+                Some(body)
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+/// Recorded EDGAR responses shipped with the tests.
+#[cfg(test)]
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/edgar");
+
+/// Client serving every request from `FIXTURES_DIR`, for tests.
+#[cfg(test)]
+pub fn offline_client() -> crate::edgar::client::EdgarClient {
+    crate::edgar::client::EdgarClient::new(crate::edgar::config::EdgarConfig::default())
+        .expect("Failed to build EDGAR client")
+        .offline(Some(FixtureStore::new(FIXTURES_DIR)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_to_fixture_path() {
+        let store = FixtureStore::new("/fixtures");
+        assert_eq!(
+            store.path_for("https://data.sec.gov/submissions/CIK0000320193.json"),
+            Some(PathBuf::from("/fixtures/data.sec.gov/submissions/CIK0000320193.json"))
+        );
+        assert_eq!(
+            store.path_for("http://127.0.0.1:8080/files/company_tickers.json?x=1"),
+            Some(PathBuf::from("/fixtures/127.0.0.1:8080/files/company_tickers.json_x=1"))
+        );
+        assert_eq!(store.path_for("https://www.sec.gov/../../etc/passwd"), None);
+        assert_eq!(store.path_for("not a url"), None);
+    }
+}
@@ -0,0 +1,93 @@
+// src/edgar/rate_limit.rs
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token-bucket rate limiter shared by every task that talks to EDGAR.
+///
+/// Tokens refill continuously at `rate_per_sec`, up to `capacity`. A caller that finds the
+/// bucket empty reserves the next token anyway (the balance goes negative) and sleeps until
+/// it would have been refilled, so concurrent callers are served in arrival order and the
+/// overall request rate never exceeds the configured limit.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate_per_sec: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `rate_per_sec` requests per second with bursts of up to `capacity`.
+    /// A capacity of 1 keeps any one-second window at or below `rate_per_sec` requests.
+    pub fn new(rate_per_sec: u32, capacity: u32) -> Self {
+        let capacity = f64::from(capacity.max(1));
+        Self {
+            rate_per_sec: f64::from(rate_per_sec.max(1)),
+            capacity,
+            state: Mutex::new(BucketState { tokens: capacity, last_refill: Instant::now() }),
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tracing::trace!("Rate limiter delaying request by {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes one token and returns how long the caller must wait before using it.
+    fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate_per_sec).min(self.capacity);
+        state.last_refill = now;
+
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate_per_sec)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_concurrent_acquires_respect_rate() {
+        // 20 req/s, no burst: 6 acquisitions need at least 5 refill intervals (250ms).
+        let limiter = Arc::new(RateLimiter::new(20, 1));
+        let start = Instant::now();
+
+        let mut handles = Vec::new();
+        for _ in 0..6 {
+            let limiter = Arc::clone(&limiter);
+            handles.push(tokio::spawn(async move { limiter.acquire().await }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(240), "Acquired too quickly: {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_burst_capacity_is_free() {
+        let limiter = RateLimiter::new(1, 3);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert!(limiter.reserve() > Duration::from_millis(900), "Fourth request should wait for a refill");
+    }
+}
@@ -0,0 +1,132 @@
+// src/edgar/retry.rs
+use crate::edgar::config::EdgarConfig;
+use crate::utils::error::EdgarError;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Exponential backoff policy for EDGAR requests.
+///
+/// Attempt `n` (1-based) that fails with a retryable error waits roughly
+/// `base_delay * 2^(n-1)`, capped at `max_delay`, with random jitter in the upper half
+/// of that window so concurrent tasks don't retry in lockstep. A `Retry-After` header
+/// from the server replaces the computed delay (still capped at `max_delay`).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first request (1 = no retries)
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &EdgarConfig) -> Self {
+        Self {
+            max_attempts: config.max_retries.saturating_add(1),
+            base_delay: Duration::from_millis(config.retry_base_delay_ms),
+            max_delay: Duration::from_millis(config.retry_max_delay_ms),
+        }
+    }
+
+    /// Whether another attempt is allowed after `attempt` attempts have failed with `error`.
+    pub fn should_retry(&self, attempt: u32, error: &EdgarError) -> bool {
+        attempt < self.max_attempts && error.is_retryable()
+    }
+
+    /// Delay before the next attempt, given the number of attempts made so far.
+    pub fn delay_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(server_delay) = retry_after {
+            return server_delay.min(self.max_delay);
+        }
+        let exponent = attempt.saturating_sub(1).min(16); // Avoid overflow on silly configs
+        let ceiling = self.base_delay.saturating_mul(1 << exponent).min(self.max_delay);
+        // Jitter: uniformly pick from [ceiling/2, ceiling)
+        ceiling.mul_f64(0.5 + 0.5 * random_unit())
+    }
+}
+
+impl EdgarError {
+    /// Classifies errors into transient (worth retrying) and fatal.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            // Timeouts, refused/reset connections and truncated bodies are transient
+            EdgarError::Network(e) => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+            // SEC answers 403 when the fair-access rate is exceeded
+            EdgarError::RateLimited => true,
+            EdgarError::Http(status) => {
+                status.is_server_error()
+                    || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || *status == reqwest::StatusCode::REQUEST_TIMEOUT
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parses a `Retry-After` header given either as delta-seconds or as an HTTP-date.
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    // A date in the past means "retry now"
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Cheap random number in [0, 1) without pulling in a RNG crate:
+/// `RandomState` is seeded randomly per instance.
+fn random_unit() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { max_attempts: 4, base_delay: Duration::from_millis(100), max_delay: Duration::from_secs(1) }
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = policy();
+        for _ in 0..20 {
+            let first = policy.delay_for(1, None);
+            assert!(first >= Duration::from_millis(50) && first < Duration::from_millis(100), "{:?}", first);
+            let third = policy.delay_for(3, None);
+            assert!(third >= Duration::from_millis(200) && third < Duration::from_millis(400), "{:?}", third);
+            assert!(policy.delay_for(10, None) < Duration::from_secs(1));
+        }
+        assert_eq!(policy.delay_for(1, Some(Duration::from_secs(30))), Duration::from_secs(1), "Retry-After is capped");
+        assert_eq!(policy.delay_for(1, Some(Duration::from_millis(300))), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let policy = policy();
+        assert!(policy.should_retry(1, &EdgarError::RateLimited));
+        assert!(policy.should_retry(3, &EdgarError::Http(reqwest::StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(!policy.should_retry(4, &EdgarError::Http(reqwest::StatusCode::SERVICE_UNAVAILABLE)), "Attempts exhausted");
+        assert!(!policy.should_retry(1, &EdgarError::FilingDocNotFound("x".to_string())));
+        assert!(!policy.should_retry(1, &EdgarError::Http(reqwest::StatusCode::BAD_REQUEST)));
+        assert!(!policy.should_retry(1, &EdgarError::Parse("bad json".to_string())));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("Sun, 06 Nov 1994 08:49:37 GMT"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,270 @@
+// src/edgar/sgml.rs
+//! Parsing of EDGAR's SGML submission formats.
+//!
+//! The submission header (`<accession>.hdr.sgml`, also served HTML-escaped as
+//! `<accession>-index-headers.html` in every filing folder) describes the filing
+//! (form type, period, filer) and lists its documents without their content:
+//!
+//! ```text
+//! <TYPE>10-K
+//! <PERIOD>20231231
+//! <FILER>
+//! <COMPANY-DATA>
+//! <CONFORMED-NAME>Test Widgets Corp
+//! <CIK>0001234567
+//! ...
+//! <DOCUMENT>
+//! <TYPE>10-K
+//! <SEQUENCE>1
+//! <FILENAME>tstc-20231231.htm
+//! </DOCUMENT>
+//! ```
+//!
+//! The full submission text file (`<accession>.txt`) carries the same `<DOCUMENT>` envelopes
+//! with each document's content between `<TEXT>` and `</TEXT>`.
+use crate::utils::content::{escape_html, is_html};
+use crate::utils::error::EdgarError;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// `<body ...>` ... `</body>` of an HTML document
+static HTML_BODY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<body[^>]*>(.*?)(?:</body>|\z)").expect("Failed to compile HTML_BODY_RE")
+});
+
+/// The filing-level fields of an SGML submission header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmissionHeader {
+    pub accession_number: String,
+    pub form_type: String,
+    /// Period of report (`YYYY-MM-DD`)
+    pub period: Option<String>,
+    /// Filing date (`YYYY-MM-DD`)
+    pub filing_date: Option<String>,
+    /// Data of the first filer listed (the registrant for periodic reports)
+    pub company_name: String,
+    pub cik: String,
+    pub sic: Option<String>,
+    /// `MMDD`, as in the submissions JSON
+    pub fiscal_year_end: Option<String>,
+    pub documents: Vec<SubmissionDocument>,
+}
+
+/// One `<DOCUMENT>` entry of a submission.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmissionDocument {
+    /// e.g. "10-K", "EX-21.1", "GRAPHIC"
+    pub doc_type: String,
+    pub sequence: Option<u32>,
+    pub filename: String,
+    pub description: Option<String>,
+    /// Content between `<TEXT>` and `</TEXT>`; only present when parsed from the full submission
+    pub text: Option<String>,
+}
+
+impl SubmissionDocument {
+    /// Whether the document is part of the report itself rather than an exhibit or attachment:
+    /// typed as the filing's form (multi-document reports repeat the form type), or an EX-13
+    /// annual report to security holders, which older 10-Ks incorporate Item 8 from.
+    pub fn is_report_part(&self, form_type: &str) -> bool {
+        let doc_type = self.doc_type.to_ascii_uppercase();
+        let base_form = form_type.trim().to_ascii_uppercase();
+        let base_form = base_form.trim_end_matches("/A");
+        doc_type.trim_end_matches("/A") == base_form || doc_type.starts_with("EX-13")
+    }
+
+    /// The document's content as an HTML fragment: the body of HTML documents, or escaped
+    /// plain text in a `<pre>` block.
+    fn html_fragment(&self) -> String {
+        let text = self.text.as_deref().unwrap_or_default();
+        if is_html(text) {
+            HTML_BODY_RE.captures(text)
+                .and_then(|caps| caps.get(1))
+                .map_or_else(|| text.to_string(), |body| body.as_str().to_string())
+        } else {
+            format!("<pre>{}</pre>", escape_html(text))
+        }
+    }
+}
+
+impl SubmissionHeader {
+    /// The main document of the filing: sequence 1, or else the first document typed as the form.
+    pub fn primary_document(&self) -> Option<&SubmissionDocument> {
+        self.documents.iter().find(|doc| doc.sequence == Some(1))
+            .or_else(|| self.documents.iter().find(|doc| doc.doc_type.eq_ignore_ascii_case(&self.form_type)))
+    }
+}
+
+/// Splits a header line into its tag and value (`<CIK>0001234567` -> ("CIK", "0001234567")).
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim_start().strip_prefix('<')?;
+    let (tag, value) = rest.split_once('>')?;
+    Some((tag.trim(), value.trim()))
+}
+
+/// Converts an SGML date (`YYYYMMDD`) to ISO format.
+fn iso_date(value: &str) -> Option<String> {
+    let date = value.get(0..8).filter(|d| d.bytes().all(|b| b.is_ascii_digit()))?;
+    Some(format!("{}-{}-{}", &date[0..4], &date[4..6], &date[6..8]))
+}
+
+/// Undoes the HTML escaping of `-index-headers.html`; plain `.hdr.sgml` text passes through unchanged.
+fn unescape_html(raw: &str) -> String {
+    raw.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&amp;", "&")
+}
+
+/// Parses an SGML submission header (raw or HTML-escaped).
+pub fn parse_submission_header(raw: &str) -> Result<SubmissionHeader, EdgarError> {
+    let text = unescape_html(raw);
+    let mut header = SubmissionHeader::default();
+    let mut document: Option<SubmissionDocument> = None;
+    let mut in_company_data = false;
+    let mut seen_company_data = false;
+
+    for (tag, value) in text.lines().filter_map(split_tag) {
+        if let Some(doc) = document.as_mut() {
+            match tag {
+                "TYPE" => doc.doc_type = value.to_string(),
+                "SEQUENCE" => doc.sequence = value.parse().ok(),
+                "FILENAME" => doc.filename = value.to_string(),
+                "DESCRIPTION" => doc.description = Some(value.to_string()).filter(|d| !d.is_empty()),
+                "/DOCUMENT" => header.documents.extend(document.take()),
+                _ => {}
+            }
+            continue;
+        }
+        match tag {
+            "DOCUMENT" => document = Some(SubmissionDocument::default()),
+            "ACCESSION-NUMBER" => header.accession_number = value.to_string(),
+            "TYPE" => header.form_type = value.to_string(),
+            "PERIOD" => header.period = iso_date(value),
+            "FILING-DATE" => header.filing_date = iso_date(value),
+            // Only the first filer's company data describes the registrant
+            "COMPANY-DATA" => in_company_data = !seen_company_data,
+            "/COMPANY-DATA" => {
+                in_company_data = false;
+                seen_company_data = true;
+            }
+            "CONFORMED-NAME" if in_company_data => header.company_name = value.to_string(),
+            "CIK" if in_company_data => header.cik = value.to_string(),
+            "ASSIGNED-SIC" if in_company_data => header.sic = Some(value.to_string()),
+            "FISCAL-YEAR-END" if in_company_data => header.fiscal_year_end = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    if header.form_type.is_empty() {
+        return Err(EdgarError::Parse("SGML submission header has no <TYPE>".to_string()));
+    }
+    Ok(header)
+}
+
+/// Parses the `<DOCUMENT>` envelopes of a full submission text file (`<accession>.txt`),
+/// including each document's `<TEXT>`.
+pub fn parse_full_submission(raw: &str) -> Result<Vec<SubmissionDocument>, EdgarError> {
+    let mut documents = Vec::new();
+    let mut rest = raw;
+    while let Some(start) = rest.find("<DOCUMENT>") {
+        let block = &rest[start + "<DOCUMENT>".len()..];
+        let end = block.find("</DOCUMENT>").unwrap_or(block.len());
+        let (block, remaining) = (&block[..end], &block[end..]);
+        rest = remaining;
+
+        // Metadata tags precede <TEXT>; the content must not be scanned for tags
+        let (meta, text) = match block.find("<TEXT>") {
+            Some(text_start) => {
+                let text = &block[text_start + "<TEXT>".len()..];
+                let text = text.rfind("</TEXT>").map_or(text, |text_end| &text[..text_end]);
+                (&block[..text_start], Some(text.trim_matches(['\r', '\n']).to_string()))
+            }
+            None => (block, None),
+        };
+        let mut document = SubmissionDocument { text, ..SubmissionDocument::default() };
+        for (tag, value) in meta.lines().filter_map(split_tag) {
+            match tag {
+                "TYPE" => document.doc_type = value.to_string(),
+                "SEQUENCE" => document.sequence = value.parse().ok(),
+                "FILENAME" => document.filename = value.to_string(),
+                "DESCRIPTION" => document.description = Some(value.to_string()).filter(|d| !d.is_empty()),
+                _ => {}
+            }
+        }
+        documents.push(document);
+    }
+
+    if documents.is_empty() {
+        return Err(EdgarError::Parse("Full submission contains no <DOCUMENT> entries".to_string()));
+    }
+    Ok(documents)
+}
+
+/// Joins documents into a single document, in the given order, so a section split across
+/// documents can be extracted in one pass. If all documents are plain text the result is plain
+/// text; otherwise it is one HTML page whose bodies are concatenated without wrappers, to keep
+/// headings from different documents siblings of each other.
+pub fn merge_documents(documents: &[&SubmissionDocument]) -> String {
+    let texts = || documents.iter().map(|doc| doc.text.as_deref().unwrap_or_default());
+    if !texts().any(is_html) {
+        return texts().collect::<Vec<_>>().join("\n");
+    }
+    let body: String = documents.iter().map(|doc| doc.html_fragment()).collect::<Vec<_>>().join("\n");
+    format!("<html><body>\n{}\n</body></html>", body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_escaped_header() {
+        let raw = "<html><body><pre>\n&lt;SEC-HEADER&gt;0000320193-23-000106.hdr.sgml : 20231103\n\
+                   &lt;ACCESSION-NUMBER&gt;0000320193-23-000106\n&lt;TYPE&gt;10-K\n&lt;PERIOD&gt;20230930\n\
+                   &lt;FILING-DATE&gt;20231103\n&lt;FILER&gt;\n&lt;COMPANY-DATA&gt;\n\
+                   &lt;CONFORMED-NAME&gt;Apple Inc.\n&lt;CIK&gt;0000320193\n&lt;FISCAL-YEAR-END&gt;0930\n\
+                   &lt;/COMPANY-DATA&gt;\n&lt;/FILER&gt;\n&lt;/SEC-HEADER&gt;\n\
+                   &lt;DOCUMENT&gt;\n&lt;TYPE&gt;EX-21.1\n&lt;SEQUENCE&gt;2\n&lt;FILENAME&gt;a10-kexhibit2112023.htm\n\
+                   &lt;DESCRIPTION&gt;EX-21.1\n&lt;/DOCUMENT&gt;\n\
+                   &lt;DOCUMENT&gt;\n&lt;TYPE&gt;10-K\n&lt;SEQUENCE&gt;1\n&lt;FILENAME&gt;aapl-20230930.htm\n\
+                   &lt;/DOCUMENT&gt;\n</pre></body></html>";
+        let header = parse_submission_header(raw).unwrap();
+        assert_eq!(header.form_type, "10-K");
+        assert_eq!(header.period.as_deref(), Some("2023-09-30"));
+        assert_eq!(header.filing_date.as_deref(), Some("2023-11-03"));
+        assert_eq!(header.company_name, "Apple Inc.");
+        assert_eq!(header.cik, "0000320193");
+        assert_eq!(header.fiscal_year_end.as_deref(), Some("0930"));
+        assert_eq!(header.documents.len(), 2);
+        assert_eq!(header.primary_document().unwrap().filename, "aapl-20230930.htm");
+        assert_eq!(header.documents[1].description, None);
+    }
+
+    #[test]
+    fn test_header_without_type_is_an_error() {
+        assert!(parse_submission_header("<html><body>Not found</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_full_submission() {
+        let raw = "<SEC-DOCUMENT>0000950123-97-002288.txt : 19970328\n<SEC-HEADER>\nCONFORMED SUBMISSION TYPE:\t10-K\n</SEC-HEADER>\n\
+                   <DOCUMENT>\n<TYPE>10-K\n<SEQUENCE>1\n<DESCRIPTION>ANNUAL REPORT\n<TEXT>\nITEM 1. BUSINESS <TYPE> is not a tag here\n</TEXT>\n</DOCUMENT>\n\
+                   <DOCUMENT>\n<TYPE>EX-13\n<SEQUENCE>2\n<FILENAME>ex13.htm\n<TEXT>\n<HTML><BODY><P>Balance sheets</P></BODY></HTML>\n</TEXT>\n</DOCUMENT>\n\
+                   <DOCUMENT>\n<TYPE>EX-27\n<SEQUENCE>3\n<TEXT>\n<TOTAL-ASSETS> 8,410\n</TEXT>\n</DOCUMENT>\n</SEC-DOCUMENT>";
+        let documents = parse_full_submission(raw).unwrap();
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents[0].doc_type, "10-K");
+        assert_eq!(documents[0].description.as_deref(), Some("ANNUAL REPORT"));
+        assert_eq!(documents[0].text.as_deref(), Some("ITEM 1. BUSINESS <TYPE> is not a tag here"));
+        assert_eq!(documents[1].filename, "ex13.htm");
+
+        let report: Vec<&SubmissionDocument> = documents.iter().filter(|d| d.is_report_part("10-K/A")).collect();
+        assert_eq!(report.len(), 2, "The 10-K document and the EX-13 annual report");
+        let merged = merge_documents(&report);
+        assert!(merged.contains("<pre>ITEM 1. BUSINESS &lt;TYPE&gt; is not a tag here</pre>"));
+        assert!(merged.contains("<P>Balance sheets</P>"));
+        assert!(!merged.contains("<HTML>"));
+        assert_eq!(merge_documents(&[&documents[0], &documents[2]]), "ITEM 1. BUSINESS <TYPE> is not a tag here\n<TOTAL-ASSETS> 8,410",
+                   "Plain-text documents stay plain text");
+
+        assert!(parse_full_submission("<html>404</html>").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+// src/extractors/definitions.rs
+//! User-defined section definitions, loaded from a TOML or JSON file (`--sections`) to override
+//! or extend the built-in catalogues without recompiling. One list per report type:
+//!
+//! ```toml
+//! [[annual]]
+//! name = "Item 7"                      # replaces the built-in Item 7
+//! title = "Management's Discussion and Analysis"
+//! start_patterns = ['(?i)^\s*Item\s*7[\.\s]+Management']
+//! end_patterns = ['(?i)^\s*Item\s*7A', '(?i)^\s*Item\s*8[\.\s]']
+//! selector = "p > b, h2, h3"           # optional, elements holding the headings
+//! min_size = 2000                      # optional, overrides --min-section-size
+//!
+//! [[quarterly]]
+//! name = "Part I Item 2"
+//! ...
+//! ```
+//!
+//! Every definition is validated when the file is loaded, so a bad pattern stops the run
+//! before any filing is downloaded.
+use crate::extractors::section::SectionSpec;
+use crate::utils::error::{AppError, ExtractError};
+use regex::Regex;
+use scraper::Selector;
+use serde::Deserialize;
+use std::path::Path;
+
+/// One section as written in the definitions file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SectionDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub title: String,
+    pub start_patterns: Vec<String>,
+    pub end_patterns: Vec<String>,
+    /// CSS selector for the elements holding the section's headings
+    pub selector: Option<String>,
+    pub min_size: Option<usize>,
+    /// Name of the section expected to follow this one
+    pub successor: Option<String>,
+}
+
+impl SectionDefinition {
+    /// Compiles the definition's patterns and selector. `context` locates the definition in
+    /// error messages, e.g. "sections.toml [[annual]] #2".
+    pub fn compile(&self, context: &str) -> Result<SectionSpec, ExtractError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(ExtractError::InvalidDefinition(format!("{}: name is empty", context)));
+        }
+        let context = format!("{} '{}'", context, name);
+        let compile = |field: &str, patterns: &[String]| -> Result<Vec<Regex>, ExtractError> {
+            if patterns.is_empty() {
+                return Err(ExtractError::InvalidDefinition(format!("{}: {} is empty", context, field)));
+            }
+            patterns.iter().enumerate()
+                .map(|(i, pattern)| Regex::new(pattern).map_err(|e| {
+                    ExtractError::RegexError(format!("{}: {}[{}] `{}`: {}", context, field, i, pattern, e))
+                }))
+                .collect()
+        };
+        let selector = self.selector.as_deref()
+            .map(|selector| Selector::parse(selector).map_err(|e| {
+                ExtractError::InvalidDefinition(format!("{}: selector `{}`: {}", context, selector, e))
+            }))
+            .transpose()?;
+
+        Ok(SectionSpec {
+            name: name.to_string(),
+            title: self.title.trim().to_string(),
+            start_patterns: compile("start_patterns", &self.start_patterns)?,
+            end_patterns: compile("end_patterns", &self.end_patterns)?,
+            successor: self.successor.clone(),
+            selector,
+            min_size: self.min_size,
+        })
+    }
+}
+
+/// On-disk layout of the definitions file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DefinitionsFile {
+    #[serde(default)]
+    annual: Vec<SectionDefinition>,
+    #[serde(default)]
+    quarterly: Vec<SectionDefinition>,
+    #[serde(default)]
+    foreign: Vec<SectionDefinition>,
+}
+
+/// Validated user-defined sections, per report type.
+#[derive(Debug, Clone, Default)]
+pub struct CustomSections {
+    /// 10-K family
+    pub annual: Vec<SectionSpec>,
+    /// 10-Q family
+    pub quarterly: Vec<SectionSpec>,
+    /// 20-F
+    pub foreign: Vec<SectionSpec>,
+}
+
+impl CustomSections {
+    /// Loads and validates a definitions file: JSON if the extension is `.json`, TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Config(format!("Failed to read section definitions {}: {}", path.display(), e)))?;
+        let json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let sections = Self::parse(&raw, json, &path.display().to_string())?;
+        tracing::info!("Loaded {} section definitions from {}",
+                       sections.annual.len() + sections.quarterly.len() + sections.foreign.len(), path.display());
+        Ok(sections)
+    }
+
+    fn parse(raw: &str, json: bool, origin: &str) -> Result<Self, AppError> {
+        let file: DefinitionsFile = if json {
+            serde_json::from_str(raw).map_err(|e| AppError::Config(format!("Invalid section definitions {}: {}", origin, e)))?
+        } else {
+            toml::from_str(raw).map_err(|e| AppError::Config(format!("Invalid section definitions {}: {}", origin, e)))?
+        };
+        Ok(Self {
+            annual: compile_list(&file.annual, &format!("{} [[annual]]", origin))?,
+            quarterly: compile_list(&file.quarterly, &format!("{} [[quarterly]]", origin))?,
+            foreign: compile_list(&file.foreign, &format!("{} [[foreign]]", origin))?,
+        })
+    }
+}
+
+fn compile_list(definitions: &[SectionDefinition], context: &str) -> Result<Vec<SectionSpec>, ExtractError> {
+    let mut specs: Vec<SectionSpec> = Vec::with_capacity(definitions.len());
+    for (i, definition) in definitions.iter().enumerate() {
+        let spec = definition.compile(&format!("{} #{}", context, i + 1))?;
+        if specs.iter().any(|other| other.name.eq_ignore_ascii_case(&spec.name)) {
+            return Err(ExtractError::InvalidDefinition(format!("{} #{}: '{}' is defined twice", context, i + 1, spec.name)));
+        }
+        specs.push(spec);
+    }
+    Ok(specs)
+}
+
+/// A built-in catalogue with `custom` applied: a definition named like a built-in section
+/// (case-insensitive) replaces it in place, keeping its name, and its title and successor unless given;
+/// other definitions are appended in file order.
+pub fn merge_catalogue(builtin: &[SectionSpec], custom: &[SectionSpec]) -> Vec<SectionSpec> {
+    let mut catalogue = builtin.to_vec();
+    for spec in custom {
+        match catalogue.iter_mut().find(|existing| existing.name.eq_ignore_ascii_case(&spec.name)) {
+            Some(existing) => {
+                let title = if spec.title.is_empty() { std::mem::take(&mut existing.title) } else { spec.title.clone() };
+                let successor = spec.successor.clone().or_else(|| existing.successor.take());
+                *existing = SectionSpec { name: existing.name.clone(), title, successor, ..spec.clone() };
+            }
+            None => catalogue.push(spec.clone()),
+        }
+    }
+    catalogue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::section::TEN_K_SECTIONS;
+
+    const DEFINITIONS: &str = r#"
+        [[annual]]
+        name = "item 7"
+        title = "MD&A"
+        start_patterns = ['(?i)^\s*Item\s*7[\.\s]+Management']
+        end_patterns = ['(?i)^\s*Item\s*8[\.\s]']
+        min_size = 2000
+
+        [[annual]]
+        name = "Auditor Report"
+        start_patterns = ['(?i)^\s*Report\s+of\s+Independent\s+Registered']
+        end_patterns = ['(?i)^\s*Consolidated\s+Balance\s+Sheets?']
+        selector = "p > b, h3"
+    "#;
+
+    #[test]
+    fn test_definitions_override_and_extend() {
+        let custom = CustomSections::parse(DEFINITIONS, false, "sections.toml").unwrap();
+        assert_eq!(custom.annual.len(), 2);
+        assert!(custom.quarterly.is_empty());
+
+        let catalogue = merge_catalogue(&TEN_K_SECTIONS, &custom.annual);
+        assert_eq!(catalogue.len(), TEN_K_SECTIONS.len() + 1);
+        // The built-in spelling is kept, for successor checks and output file names
+        let mdna = catalogue.iter().find(|spec| spec.name == "Item 7").unwrap();
+        assert!(!catalogue.iter().any(|spec| spec.name == "item 7"));
+        assert_eq!((mdna.title.as_str(), mdna.min_size), ("MD&A", Some(2000)));
+        assert_eq!(mdna.successor.as_deref(), Some("Item 7A"));
+        assert!(catalogue.last().unwrap().selector.is_some());
+
+        let json = r#"{"foreign": [{"name": "Item 18", "start_patterns": ["Item 18"], "end_patterns": ["Item 19"]}]}"#;
+        assert_eq!(CustomSections::parse(json, true, "sections.json").unwrap().foreign.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let bad_regex = DEFINITIONS.replace(r"Item\s*8[\.\s]", r"Item\s*8[\.\s");
+        match CustomSections::parse(&bad_regex, false, "sections.toml") {
+            Err(AppError::Extraction(ExtractError::RegexError(message))) => {
+                assert!(message.starts_with("sections.toml [[annual]] #1 'item 7': end_patterns[0]"), "{}", message);
+            }
+            other => panic!("expected a regex error, got {:?}", other),
+        }
+
+        let bad_selector = DEFINITIONS.replace("p > b, h3", "p >");
+        assert!(matches!(CustomSections::parse(&bad_selector, false, "sections.toml"),
+                         Err(AppError::Extraction(ExtractError::InvalidDefinition(_)))));
+        let typo = DEFINITIONS.replace("min_size", "minimum_size");
+        assert!(matches!(CustomSections::parse(&typo, false, "sections.toml"), Err(AppError::Config(_))));
+        let duplicate = DEFINITIONS.replace("Auditor Report", "Item 7");
+        assert!(CustomSections::parse(&duplicate, false, "sections.toml").is_err());
+    }
+}
@@ -1,5 +1,70 @@
 // src/extractors/mod.rs
+pub mod definitions;
+pub mod quality;
 pub mod section;
+pub mod text;
 // Potentially add later:
 // pub mod xbrl;
-// pub mod table;
\ No newline at end of file
+// pub mod table;
+
+use crate::utils::content::is_html;
+use crate::utils::error::ExtractError;
+use std::sync::Arc;
+use section::{DomExtractor, ExtractedSection, FilingContext, SectionSpec};
+use text::TextExtractor;
+
+/// Extracts a section with the extractor suited to the document's content type:
+/// DOM traversal for HTML, line-based matching for plain-text filings.
+pub fn extract_section(
+    content: &str,
+    spec: &SectionSpec,
+    filing: &FilingContext,
+    min_section_size: usize,
+) -> Result<ExtractedSection, ExtractError> {
+    if is_html(content) {
+        DomExtractor::new().extract_section(content, spec, filing, min_section_size)
+    } else {
+        tracing::debug!("No HTML markup in document for {}-{}, using the text extractor", filing.ticker, filing.filing_year);
+        TextExtractor::new().extract_section(content, spec, filing, min_section_size)
+    }
+}
+
+/// Extracts every section of `specs` the document contains. A single spec goes through
+/// `extract_section`, keeping its error; with several, sections that aren't found are skipped
+/// and only finding none at all is an error.
+pub fn extract_sections(
+    content: &str,
+    specs: &[SectionSpec],
+    filing: &FilingContext,
+    min_section_size: usize,
+) -> Result<Vec<ExtractedSection>, ExtractError> {
+    if let [spec] = specs {
+        return extract_section(content, spec, filing, min_section_size).map(|section| vec![section]);
+    }
+    let sections: Vec<ExtractedSection> = if is_html(content) {
+        DomExtractor::new().extract_all_sections(content, specs, filing, min_section_size)
+    } else {
+        let extractor = TextExtractor::new();
+        specs.iter()
+            .filter_map(|spec| extractor.extract_section(content, spec, filing, min_section_size).ok())
+            .collect()
+    };
+    if sections.is_empty() {
+        return Err(ExtractError::SectionNotFound(format!("None of the {} sections found for {}-{}",
+                                                         specs.len(), filing.ticker, filing.filing_year)));
+    }
+    Ok(sections)
+}
+
+/// Runs `extract_sections` on the blocking thread pool: parsing a large filing into a DOM is
+/// CPU-bound and would otherwise hold up the async tasks downloading other filings.
+pub async fn extract_sections_blocking(
+    content: String,
+    specs: Arc<Vec<SectionSpec>>,
+    filing: FilingContext,
+    min_section_size: usize,
+) -> Result<Vec<ExtractedSection>, ExtractError> {
+    tokio::task::spawn_blocking(move || extract_sections(&content, &specs, &filing, min_section_size))
+        .await
+        .map_err(|e| ExtractError::HtmlParseError(format!("Extraction task failed: {}", e)))?
+}
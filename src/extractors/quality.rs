@@ -0,0 +1,174 @@
+// src/extractors/quality.rs
+//! Quality assessment of extracted sections: a confidence score between 0 and 1 and flags
+//! naming what looks wrong, written to the section's metadata so low-quality extractions can
+//! be triaged without reading them.
+use crate::extractors::section::{is_toc_like, ExtractedSection, SectionSpec, ITEM_MENTION_RE};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"<[^>]*>").expect("Failed to compile TAG_RE")
+});
+
+// HTML tables, or SGML tables of plain-text filings (escaped in their `<pre>` block)
+static TABLE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)<table\b|&lt;table&gt;").expect("Failed to compile TABLE_RE")
+});
+
+static FINANCIAL_STATEMENTS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bfinancial\s+statements\b").expect("Failed to compile FINANCIAL_STATEMENTS_RE")
+});
+
+static AUDITOR_REPORT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)Report\s+of\s+Independent\s+(?:Registered\s+)?(?:Public\s+)?(?:Accounting\s+Firm|Auditors?)|Independent\s+Auditors?\W{0,2}s?\s+Report")
+        .expect("Failed to compile AUDITOR_REPORT_RE")
+});
+
+/// Share of the document below which a section is suspiciously short.
+const MIN_DOCUMENT_SHARE: f64 = 0.002;
+/// Share of the document above which a section is suspiciously long (a missed end marker).
+const MAX_DOCUMENT_SHARE: f64 = 0.8;
+/// Sections with fewer text characters (whitespace excluded) are suspiciously short.
+const MIN_TEXT_CHARS: usize = 200;
+
+/// How a section's boundaries were found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryMethod {
+    /// Followed the section's entry in a linked Table of Contents
+    TocLink,
+    /// Best-scoring heading found by text matching
+    #[default]
+    TextMatch,
+    /// Heading lines of a plain-text filing
+    PlainText,
+}
+
+impl BoundaryMethod {
+    fn confidence(self) -> f64 {
+        match self {
+            BoundaryMethod::TocLink => 1.0,
+            BoundaryMethod::TextMatch => 0.9,
+            BoundaryMethod::PlainText => 0.8,
+        }
+    }
+}
+
+/// What the extractor knows about how a section was found, besides its content.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Evidence {
+    pub method: BoundaryMethod,
+    /// No end marker was found; the section ends at the next section's start
+    pub fallback_end: bool,
+    /// The section ends at an item other than its expected successor
+    pub unexpected_successor: bool,
+    /// The start reads like a cross-reference rather than a heading
+    pub weak_start: bool,
+    /// Another start candidate scored almost as well
+    pub ambiguous_start: bool,
+    /// Size of the whole document in bytes
+    pub document_len: usize,
+}
+
+/// Something about an extracted section that makes it less trustworthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityFlag {
+    FallbackEnd,
+    UnexpectedSuccessor,
+    WeakStart,
+    AmbiguousStart,
+    /// Many item references for the amount of text, as in a Table of Contents
+    TocLikeDensity,
+    /// Small share of the document, or little text
+    SuspiciouslyShort,
+    /// Most of the document
+    SuspiciouslyLong,
+    /// Financial statements without any table
+    NoTables,
+    /// Annual financial statements without the auditor's report
+    MissingAuditorReport,
+}
+
+impl QualityFlag {
+    /// Factor applied to the confidence.
+    fn penalty(self) -> f64 {
+        match self {
+            QualityFlag::FallbackEnd => 0.7,
+            QualityFlag::UnexpectedSuccessor => 0.9,
+            QualityFlag::WeakStart => 0.6,
+            QualityFlag::AmbiguousStart => 0.8,
+            QualityFlag::TocLikeDensity => 0.4,
+            QualityFlag::SuspiciouslyShort => 0.7,
+            QualityFlag::SuspiciouslyLong => 0.6,
+            QualityFlag::NoTables => 0.6,
+            QualityFlag::MissingAuditorReport => 0.8,
+        }
+    }
+}
+
+/// Flags for `section` (extracted for `spec`) and the resulting confidence, rounded to two
+/// decimals. Table and auditor's report checks only apply to financial statement sections,
+/// the latter only to annual reports.
+pub fn assess(section: &ExtractedSection, spec: &SectionSpec, evidence: &Evidence) -> (f64, Vec<QualityFlag>) {
+    let html = &section.content_html;
+    let text = TAG_RE.replace_all(html, " ").replace("&nbsp;", " ").replace("&#160;", " ");
+    let chars = text.chars().filter(|c| !c.is_whitespace()).count();
+    let share = html.len() as f64 / evidence.document_len.max(1) as f64;
+    let financials = FINANCIAL_STATEMENTS_RE.is_match(&spec.title);
+
+    let checks = [
+        (evidence.fallback_end, QualityFlag::FallbackEnd),
+        (evidence.unexpected_successor, QualityFlag::UnexpectedSuccessor),
+        (evidence.weak_start, QualityFlag::WeakStart),
+        (evidence.ambiguous_start, QualityFlag::AmbiguousStart),
+        (is_toc_like(ITEM_MENTION_RE.find_iter(&text).count(), chars), QualityFlag::TocLikeDensity),
+        (share < MIN_DOCUMENT_SHARE || chars < MIN_TEXT_CHARS, QualityFlag::SuspiciouslyShort),
+        (share > MAX_DOCUMENT_SHARE, QualityFlag::SuspiciouslyLong),
+        (financials && !TABLE_RE.is_match(html), QualityFlag::NoTables),
+        (financials && section.fiscal_quarter.is_none() && !AUDITOR_REPORT_RE.is_match(&text), QualityFlag::MissingAuditorReport),
+    ];
+    let flags: Vec<QualityFlag> = checks.into_iter().filter(|(raised, _)| *raised).map(|(_, flag)| flag).collect();
+    let confidence = flags.iter().fold(evidence.method.confidence(), |confidence, flag| confidence * flag.penalty());
+    ((confidence * 100.0).round() / 100.0, flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::section::{FilingContext, TEN_K_ITEM_8, ten_k_section};
+
+    fn section(spec: &SectionSpec, html: &str) -> ExtractedSection {
+        ExtractedSection::new(spec, &FilingContext::default(), html.to_string())
+    }
+
+    #[test]
+    fn test_assess_financial_statements() {
+        let body = "<p>Report of Independent Registered Public Accounting Firm</p><p>We have audited the accompanying consolidated balance sheets of the Company as of December 31, 2023 and 2022, and the related statements of operations.</p><p>In our opinion, the consolidated financial statements present fairly, in all material respects, the financial position of the Company.</p><table><tr><td>Total assets</td><td>1,234</td></tr></table>";
+        let evidence = Evidence { method: BoundaryMethod::TocLink, document_len: body.len() * 10, ..Evidence::default() };
+        assert_eq!(assess(&section(&TEN_K_ITEM_8, body), &TEN_K_ITEM_8, &evidence), (1.0, vec![]));
+
+        let unaudited = body.replace("Report of Independent Registered Public Accounting Firm", "Balance Sheets")
+            .replace("<table><tr><td>Total assets</td><td>1,234</td></tr></table>", "");
+        let evidence = Evidence { fallback_end: true, ..evidence };
+        let (confidence, flags) = assess(&section(&TEN_K_ITEM_8, &unaudited), &TEN_K_ITEM_8, &evidence);
+        assert_eq!(flags, [QualityFlag::FallbackEnd, QualityFlag::NoTables, QualityFlag::MissingAuditorReport]);
+        assert_eq!(confidence, 0.34);
+    }
+
+    #[test]
+    fn test_assess_toc_like_and_size() {
+        let toc = "<p>Item 1. Business 3</p><p>Item 1A. Risk Factors 9</p><p>Item 2. Properties 20</p><p>Item 3. Legal Proceedings 21</p>";
+        let risk = ten_k_section("Item 1A").unwrap();
+        let evidence = Evidence { weak_start: true, document_len: 1_000_000, ..Evidence::default() };
+        let (confidence, flags) = assess(&section(risk, toc), risk, &evidence);
+        assert_eq!(flags, [QualityFlag::WeakStart, QualityFlag::TocLikeDensity, QualityFlag::SuspiciouslyShort]);
+        assert!(confidence < 0.2);
+
+        // Risk factors need neither tables nor an auditor's report, but shouldn't be the whole document
+        let long = format!("<p>{}</p>", "Our business is subject to risks. ".repeat(50));
+        let evidence = Evidence { document_len: long.len(), ..Evidence::default() };
+        assert_eq!(assess(&section(risk, &long), risk, &evidence), (0.54, vec![QualityFlag::SuspiciouslyLong]));
+    }
+}
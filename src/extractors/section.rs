@@ -5,10 +5,16 @@ use crate::utils::error::ExtractError;
 use regex::Regex;
 use scraper::{Html, Selector, ElementRef, node::Node}; // Import Node enum if needed for pattern matching
 use once_cell::sync::Lazy;
+use crate::extractors::quality::{self, BoundaryMethod, Evidence, QualityFlag};
+use crate::utils::content::escape_html;
+use ego_tree::NodeRef;
+use serde::Serialize;
+use std::collections::HashMap;
 
 // --- Constants ---
 // We might still need some constants, but many old ones related to byte offsets are gone.
 // const START_VALIDATION_LOOKAHEAD: usize = 5000; // May adapt this concept later if needed
+#[allow(dead_code)]
 const FALLBACK_END_CHUNK_SIZE: usize = 350_000; // Might still need a fallback size limit
 
 // --- CSS Selectors (Lazy Static) ---
@@ -25,7 +31,63 @@ static TOC_CONTAINER_SELECTOR: Lazy<Selector> = Lazy::new(|| {
         .expect("Failed to compile TOC_CONTAINER_SELECTOR")
 });
 
+// Internal links, as used by linked Tables of Contents
+static INTERNAL_LINK_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("a[href^='#']").expect("Failed to compile INTERNAL_LINK_SELECTOR")
+});
+
+/// Longest text (in characters) still treated as a heading when resolving ToC link targets.
+const MAX_HEADING_CHARS: usize = 300;
+
+// Bold text set through inline styles, as in most EDGAR filings converted from Word
+static BOLD_STYLE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)font-weight\s*:\s*(?:bold|[6-9]00)").expect("Failed to compile BOLD_STYLE_RE")
+});
+
+// Any reference to an item, counted to spot ToC-like stretches of text
+pub(crate) static ITEM_MENTION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bItem\s*\d{1,2}[A-C]?\b").expect("Failed to compile ITEM_MENTION_RE")
+});
+
+// --- Boundary Candidate Scoring ---
+/// Start candidates scored per section; later matches are ignored.
+const MAX_START_CANDIDATES: usize = 20;
+/// End markers considered after each start candidate.
+const MAX_END_CANDIDATES: usize = 3;
+/// Longest text (in characters) treated as a standalone heading rather than a sentence.
+const MAX_STANDALONE_CHARS: usize = 200;
+/// Fraction of the document (cover page, ToC) where starts are penalized.
+const EARLY_POSITION: f64 = 0.02;
+/// Sections with fewer text characters are suspiciously short.
+const SHORT_SECTION_CHARS: usize = 200;
+/// Item references per 1,000 characters above which a section reads like a ToC,
+/// given at least `MIN_TOC_ITEMS` of them.
+const TOC_ITEM_DENSITY: f64 = 5.0;
+const MIN_TOC_ITEMS: usize = 3;
+
+const SCORE_HEADING_TAG: f64 = 2.0;
+const SCORE_BOLD: f64 = 1.5;
+const SCORE_CAPS: f64 = 1.0;
+const SCORE_STANDALONE: f64 = 2.0;
+const SCORE_CROSS_REFERENCE: f64 = -4.0;
+/// Weight of the end marker's heading score relative to the start's.
+const END_HEADING_WEIGHT: f64 = 0.5;
+const SCORE_NO_END: f64 = -5.0;
+/// Per end marker passed over, so the first plausible end wins.
+const SCORE_SKIPPED_END: f64 = -1.5;
+const SCORE_EARLY_POSITION: f64 = -1.0;
+const SCORE_EMPTY_SECTION: f64 = -5.0;
+const SCORE_SHORT_SECTION: f64 = -2.0;
+/// Per order of magnitude of section text, capped at `MAX_LENGTH_SCORE`.
+const SCORE_LENGTH: f64 = 0.5;
+const MAX_LENGTH_SCORE: f64 = 3.0;
+const SCORE_TABLES: f64 = 0.5;
+const SCORE_TOC_DENSITY: f64 = -3.0;
+/// Score difference under which another start makes the choice ambiguous.
+const AMBIGUOUS_MARGIN: f64 = 1.0;
+
 // Selectors for common block elements that might signal end of ToC visually
+#[allow(dead_code)]
 static TOC_END_SIBLING_SELECTOR: Lazy<Selector> = Lazy::new(|| {
     Selector::parse("h1, h2, h3, table, hr, div[style*='page-break']") // Elements likely following a ToC
         .expect("Failed to compile TOC_END_SIBLING_SELECTOR")
@@ -77,45 +139,431 @@ static ITEM_8_END_TEXT_RE: Lazy<Vec<Regex>> = Lazy::new(|| {
     .collect()
 });
 
+// --- Section Definitions ---
+/// Definition of a filing section: its name/title and the text patterns marking where it starts and ends.
+#[derive(Debug, Clone)]
+pub struct SectionSpec {
+    pub name: String,  // e.g., "Item 8", "Part I Item 2"
+    pub title: String, // e.g., "Financial Statements and Supplementary Data"
+    pub start_patterns: Vec<Regex>,
+    pub end_patterns: Vec<Regex>,
+    /// Name of the item that normally follows this one in the filing, if any
+    pub successor: Option<String>,
+    /// Elements that may hold the section's headings (default: `POTENTIAL_HEADER_SELECTOR`)
+    pub selector: Option<Selector>,
+    /// Minimum section size in bytes, overriding the run's `--min-section-size`
+    pub min_size: Option<usize>,
+}
+
+impl SectionSpec {
+    /// Builds a spec from built-in pattern strings. Invalid built-in patterns are a programming error.
+    fn from_patterns(name: &str, title: &str, start_patterns: &[&str], end_patterns: &[&str]) -> Self {
+        let compile = |patterns: &[&str]| -> Vec<Regex> {
+            patterns.iter()
+                .map(|pat| Regex::new(pat).unwrap_or_else(|e| panic!("Invalid built-in pattern for {}: {}", name, e)))
+                .collect()
+        };
+        Self {
+            name: name.to_string(),
+            title: title.to_string(),
+            start_patterns: compile(start_patterns),
+            end_patterns: compile(end_patterns),
+            successor: None,
+            selector: None,
+            min_size: None,
+        }
+    }
+
+    /// Selector for the elements checked against the start and end patterns.
+    pub fn header_selector(&self) -> &Selector {
+        self.selector.as_ref().unwrap_or(&POTENTIAL_HEADER_SELECTOR)
+    }
+
+    /// Builds specs for consecutive items within one part of a filing. Each item starts at its
+    /// own heading and ends at the heading of any later item in the list, or at one of the
+    /// `terminators` (e.g. the next Part) if the following items are omitted.
+    /// `items` are (name, title, heading pattern).
+    fn sequence(items: &[(&str, &str, &str)], terminators: &[&str]) -> Vec<Self> {
+        items.iter().enumerate()
+            .map(|(i, (name, title, heading))| {
+                let ends: Vec<&str> = items[i + 1..].iter().map(|(_, _, h)| *h)
+                    .chain(terminators.iter().copied())
+                    .collect();
+                Self::from_patterns(name, title, &[heading], &ends)
+            })
+            .collect()
+    }
+
+    /// Sets each spec's successor to the spec after it, across the parts of a form.
+    fn linked(mut specs: Vec<Self>) -> Vec<Self> {
+        let names: Vec<String> = specs.iter().map(|spec| spec.name.clone()).collect();
+        for (spec, next) in specs.iter_mut().zip(names.into_iter().skip(1)) {
+            spec.successor = Some(next);
+        }
+        specs
+    }
+}
+
+/// 10-K section model, Part I through Part IV. Headings must begin the element's text, so a
+/// cross-reference such as "see Item 7. Management's Discussion ..." in running text is not taken
+/// for the item itself. Item 8 keeps its own tuned patterns (see above).
+pub static TEN_K_SECTIONS: Lazy<Vec<SectionSpec>> = Lazy::new(|| {
+    let mut specs = SectionSpec::sequence(
+        &[
+            ("Item 1", "Business", r"(?i)^\s*Item\s*1[\.\s\-–—:]+Business\b"),
+            ("Item 1A", "Risk Factors", r"(?i)^\s*Item\s*1A[\.\s\-–—:]+Risk\s*Factors"),
+            ("Item 1B", "Unresolved Staff Comments", r"(?i)^\s*Item\s*1B[\.\s\-–—:]+Unresolved\s*Staff"),
+            ("Item 1C", "Cybersecurity", r"(?i)^\s*Item\s*1C[\.\s\-–—:]+Cybersecurity"),
+            ("Item 2", "Properties", r"(?i)^\s*Item\s*2[\.\s\-–—:]+(?:Description\s*of\s*)?Propert"),
+            ("Item 3", "Legal Proceedings", r"(?i)^\s*Item\s*3[\.\s\-–—:]+Legal\s*Proceedings"),
+            ("Item 4", "Mine Safety Disclosures",
+             r"(?i)^\s*Item\s*4[\.\s\-–—:]+(?:Mine\s*Safety|Submission\s*of\s*Matters|\[?\(?(?:Removed\s*and\s*)?Reserved)"),
+            ("Item 5", "Market for Registrant's Common Equity, Related Stockholder Matters and Issuer Purchases of Equity Securities",
+             r"(?i)^\s*Item\s*5[\.\s\-–—:]+Market\s*for"),
+            ("Item 6", "[Reserved]",
+             r"(?i)^\s*Item\s*6[\.\s\-–—:]+(?:Selected\s*(?:Consolidated\s*)?Financial\s*Data|\[?\(?Reserved)"),
+            ("Item 7", "Management's Discussion and Analysis of Financial Condition and Results of Operations",
+             r"(?i)^\s*Item\s*7[\.\s\-–—:]+Management\W?s?\s*Discussion"),
+            ("Item 7A", "Quantitative and Qualitative Disclosures About Market Risk",
+             r"(?i)^\s*Item\s*7A[\.\s\-–—:]+Quantitative\s*and\s*Qualitative"),
+            ("Item 8", "Financial Statements and Supplementary Data", r"(?i)^\s*Item\s*8[\.\s\-–—:]+Financial\s*Statements"),
+            ("Item 9", "Changes in and Disagreements With Accountants on Accounting and Financial Disclosure",
+             r"(?i)^\s*Item\s*9[\.\s\-–—:]+Changes\s*in"),
+            ("Item 9A", "Controls and Procedures", r"(?i)^\s*Item\s*9A(?:\(T\))?[\.\s\-–—:]+Controls\s*and\s*Procedures"),
+            ("Item 9B", "Other Information", r"(?i)^\s*Item\s*9B[\.\s\-–—:]+Other\s*Information"),
+            ("Item 9C", "Disclosure Regarding Foreign Jurisdictions that Prevent Inspections",
+             r"(?i)^\s*Item\s*9C[\.\s\-–—:]+Disclosure\s*Regarding"),
+            ("Item 10", "Directors, Executive Officers and Corporate Governance", r"(?i)^\s*Item\s*10[\.\s\-–—:]+Directors"),
+            ("Item 11", "Executive Compensation", r"(?i)^\s*Item\s*11[\.\s\-–—:]+Executive\s*Compensation"),
+            ("Item 12", "Security Ownership of Certain Beneficial Owners and Management and Related Stockholder Matters",
+             r"(?i)^\s*Item\s*12[\.\s\-–—:]+Security\s*Ownership"),
+            ("Item 13", "Certain Relationships and Related Transactions, and Director Independence",
+             r"(?i)^\s*Item\s*13[\.\s\-–—:]+Certain\s*Relationships"),
+            ("Item 14", "Principal Accountant Fees and Services", r"(?i)^\s*Item\s*14[\.\s\-–—:]+Principal\s*Account"),
+            ("Item 15", "Exhibits and Financial Statement Schedules", r"(?i)^\s*Item\s*15[\.\s\-–—:]+Exhibits"),
+            ("Item 16", "Form 10-K Summary", r"(?i)^\s*Item\s*16[\.\s\-–—:]+Form\s*10-K\s*Summary"),
+        ],
+        // Part headings end the last item present in the previous part
+        &[r"(?i)^\s*PART\s+(?:II|III|IV)\b", r"(?i)^\s*SIGNATURES?\b"],
+    );
+    if let Some(item_8) = specs.iter_mut().find(|spec| spec.name == "Item 8") {
+        item_8.start_patterns = ITEM_8_START_TEXT_RE.clone();
+        item_8.end_patterns = ITEM_8_END_TEXT_RE.clone();
+    }
+    SectionSpec::linked(specs)
+});
+
+/// 10-K Item 8, the default section for annual reports.
+pub static TEN_K_ITEM_8: Lazy<SectionSpec> = Lazy::new(|| {
+    ten_k_section("Item 8").expect("Item 8 is in the 10-K catalogue").clone()
+});
+
+/// 10-Q section model: Part I (Financial Information) and Part II (Other Information).
+/// "Item 1" exists in both parts, so headings are matched together with their titles.
+pub static TEN_Q_SECTIONS: Lazy<Vec<SectionSpec>> = Lazy::new(|| {
+    let mut specs = SectionSpec::sequence(
+        &[
+            ("Part I Item 1", "Financial Statements",
+             r"(?i)\bItem\s*1[\.\s\-–—:]+(?:Condensed\s+)?(?:Consolidated\s+)?(?:Interim\s+)?Financial\s*Statements"),
+            ("Part I Item 2", "Management's Discussion and Analysis of Financial Condition and Results of Operations",
+             r"(?i)\bItem\s*2[\.\s\-–—:]+Management\W?s?\s*Discussion"),
+            ("Part I Item 3", "Quantitative and Qualitative Disclosures About Market Risk",
+             r"(?i)\bItem\s*3[\.\s\-–—:]+Quantitative\s*and\s*Qualitative"),
+            ("Part I Item 4", "Controls and Procedures",
+             r"(?i)\bItem\s*4[\.\s\-–—:]+Controls\s*and\s*Procedures"),
+        ],
+        &[
+            r"(?i)^\s*PART\s+II\b",
+            r"(?i)\bPART\s+II\b\W*OTHER\s+INFORMATION",
+            r"(?i)\bItem\s*1[\.\s\-–—:]+Legal\s*Proceedings",
+        ],
+    );
+    specs.extend(SectionSpec::sequence(
+        &[
+            ("Part II Item 1", "Legal Proceedings", r"(?i)\bItem\s*1[\.\s\-–—:]+Legal\s*Proceedings"),
+            ("Part II Item 1A", "Risk Factors", r"(?i)\bItem\s*1A[\.\s\-–—:]+Risk\s*Factors"),
+            ("Part II Item 2", "Unregistered Sales of Equity Securities and Use of Proceeds",
+             r"(?i)\bItem\s*2[\.\s\-–—:]+Unregistered\s*Sales"),
+            ("Part II Item 3", "Defaults Upon Senior Securities", r"(?i)\bItem\s*3[\.\s\-–—:]+Defaults\s*Upon"),
+            ("Part II Item 4", "Mine Safety Disclosures", r"(?i)\bItem\s*4[\.\s\-–—:]+(?:Mine\s*Safety|Submission\s*of\s*Matters)"),
+            ("Part II Item 5", "Other Information", r"(?i)\bItem\s*5[\.\s\-–—:]+Other\s*Information"),
+            ("Part II Item 6", "Exhibits", r"(?i)\bItem\s*6[\.\s\-–—:]+Exhibits"),
+        ],
+        &[r"(?i)^\s*SIGNATURES?\b", r"(?i)\bEXHIBIT\s+INDEX\b"],
+    ));
+    SectionSpec::linked(specs)
+});
+
+/// 20-F section model (foreign private issuers). Item numbers are unique across the form's
+/// three parts, so headings are matched by number plus the leading words of the title.
+/// Note that many 20-Fs answer Item 18 with a reference to F-pages placed after the signatures.
+pub static TWENTY_F_SECTIONS: Lazy<Vec<SectionSpec>> = Lazy::new(|| {
+    let mut specs = SectionSpec::sequence(
+        &[
+            ("Item 1", "Identity of Directors, Senior Management and Advisers", r"(?i)\bItem\s*1[\.\s\-–—:]+Identity\s*of\s*Directors"),
+            ("Item 2", "Offer Statistics and Expected Timetable", r"(?i)\bItem\s*2[\.\s\-–—:]+Offer\s*Statistics"),
+            ("Item 3", "Key Information", r"(?i)\bItem\s*3[\.\s\-–—:]+Key\s*Information"),
+            ("Item 4", "Information on the Company", r"(?i)\bItem\s*4[\.\s\-–—:]+Information\s*on\s*the\s*Company"),
+            ("Item 4A", "Unresolved Staff Comments", r"(?i)\bItem\s*4A[\.\s\-–—:]+Unresolved\s*Staff"),
+            ("Item 5", "Operating and Financial Review and Prospects", r"(?i)\bItem\s*5[\.\s\-–—:]+Operating\s*and\s*Financial\s*Review"),
+            ("Item 6", "Directors, Senior Management and Employees", r"(?i)\bItem\s*6[\.\s\-–—:]+Directors,?\s*Senior\s*Management"),
+            ("Item 7", "Major Shareholders and Related Party Transactions", r"(?i)\bItem\s*7[\.\s\-–—:]+Major\s*Shareholders"),
+            ("Item 8", "Financial Information", r"(?i)\bItem\s*8[\.\s\-–—:]+Financial\s*Information"),
+            ("Item 9", "The Offer and Listing", r"(?i)\bItem\s*9[\.\s\-–—:]+(?:The\s*)?Offer\s*and\s*Listing"),
+            ("Item 10", "Additional Information", r"(?i)\bItem\s*10[\.\s\-–—:]+Additional\s*Information"),
+            ("Item 11", "Quantitative and Qualitative Disclosures About Market Risk", r"(?i)\bItem\s*11[\.\s\-–—:]+Quantitative\s*and\s*Qualitative"),
+            ("Item 12", "Description of Securities Other Than Equity Securities", r"(?i)\bItem\s*12[\.\s\-–—:]+Description\s*of\s*Securities"),
+        ],
+        &[r"(?i)^\s*PART\s+II\b"],
+    );
+    specs.extend(SectionSpec::sequence(
+        &[
+            ("Item 13", "Defaults, Dividend Arrearages and Delinquencies", r"(?i)\bItem\s*13[\.\s\-–—:]+Defaults"),
+            ("Item 14", "Material Modifications to the Rights of Security Holders and Use of Proceeds", r"(?i)\bItem\s*14[\.\s\-–—:]+Material\s*Modifications"),
+            ("Item 15", "Controls and Procedures", r"(?i)\bItem\s*15[\.\s\-–—:]+Controls\s*and\s*Procedures"),
+            ("Item 16A", "Audit Committee Financial Expert", r"(?i)\bItem\s*16A[\.\s\-–—:]+Audit\s*Committee"),
+            ("Item 16B", "Code of Ethics", r"(?i)\bItem\s*16B[\.\s\-–—:]+Code\s*of\s*Ethics"),
+            ("Item 16C", "Principal Accountant Fees and Services", r"(?i)\bItem\s*16C[\.\s\-–—:]+Principal\s*Accountant"),
+            ("Item 16D", "Exemptions from the Listing Standards for Audit Committees", r"(?i)\bItem\s*16D[\.\s\-–—:]+Exemptions"),
+            ("Item 16E", "Purchases of Equity Securities by the Issuer and Affiliated Purchasers", r"(?i)\bItem\s*16E[\.\s\-–—:]+Purchases"),
+            ("Item 16F", "Change in Registrant's Certifying Accountant", r"(?i)\bItem\s*16F[\.\s\-–—:]+Change\s*in"),
+            ("Item 16G", "Corporate Governance", r"(?i)\bItem\s*16G[\.\s\-–—:]+Corporate\s*Governance"),
+            ("Item 16H", "Mine Safety Disclosure", r"(?i)\bItem\s*16H[\.\s\-–—:]+Mine\s*Safety"),
+            ("Item 16I", "Disclosure Regarding Foreign Jurisdictions that Prevent Inspections", r"(?i)\bItem\s*16I[\.\s\-–—:]+Disclosure\s*Regarding"),
+            ("Item 16J", "Insider Trading Policies", r"(?i)\bItem\s*16J[\.\s\-–—:]+Insider\s*Trading"),
+            ("Item 16K", "Cybersecurity", r"(?i)\bItem\s*16K[\.\s\-–—:]+Cybersecurity"),
+        ],
+        &[r"(?i)^\s*PART\s+III\b"],
+    ));
+    specs.extend(SectionSpec::sequence(
+        &[
+            ("Item 17", "Financial Statements", r"(?i)\bItem\s*17[\.\s\-–—:]+Financial\s*Statements"),
+            ("Item 18", "Financial Statements", r"(?i)\bItem\s*18[\.\s\-–—:]+Financial\s*Statements"),
+            ("Item 19", "Exhibits", r"(?i)\bItem\s*19[\.\s\-–—:]+Exhibits"),
+        ],
+        &[r"(?i)^\s*SIGNATURES?\b", r"(?i)\bEXHIBIT\s+INDEX\b"],
+    ));
+    SectionSpec::linked(specs)
+});
+
+fn find_spec<'a>(specs: &'a [SectionSpec], name: &str) -> Option<&'a SectionSpec> {
+    specs.iter().find(|spec| spec.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Looks up a 10-K section by name (case-insensitive, e.g. "Item 1A").
+pub fn ten_k_section(name: &str) -> Option<&'static SectionSpec> {
+    find_spec(&TEN_K_SECTIONS, name)
+}
+
+/// Looks up a 10-Q section by name (case-insensitive, e.g. "Part I Item 2").
+pub fn ten_q_section(name: &str) -> Option<&'static SectionSpec> {
+    find_spec(&TEN_Q_SECTIONS, name)
+}
+
+/// Looks up a 20-F section by name (case-insensitive, e.g. "Item 18").
+pub fn twenty_f_section(name: &str) -> Option<&'static SectionSpec> {
+    find_spec(&TWENTY_F_SECTIONS, name)
+}
+
 // --- Data Structures ---
+/// Identifies the filing a section is extracted from; copied into every `ExtractedSection`.
+#[derive(Debug, Clone, Default)]
+pub struct FilingContext {
+    pub ticker: String,
+    pub company_name: String,
+    pub filing_year: u32,
+    pub form_type: String,        // e.g., "10-K", "10-K405", "10-K/A"
+    pub accession_number: String,
+    pub is_amendment: bool,
+    pub fiscal_quarter: Option<u8>, // Set for quarterly reports (10-Q)
+    pub period_end: Option<String>, // Period end date (YYYY-MM-DD), if known
+}
+
 #[derive(Debug, Clone)]
 pub struct ExtractedSection {
     pub section_name: String,  // e.g., "Item 8"
     pub section_title: String, // e.g., "Financial Statements and Supplementary Data" (best effort)
     pub content_html: String,  // The raw HTML content of the section
-    pub filing_year: u32,      // The year of the filing
+    pub filing_year: u32,      // Fiscal year of the report (kept as "filing_year" in output metadata)
     pub company_name: String,  // Company name
     pub ticker: String,        // Ticker symbol
+    pub form_type: String,     // Form the section came from
+    pub accession_number: String,
+    pub is_amendment: bool,    // Extracted from an amended filing ("<form>/A")
+    pub fiscal_quarter: Option<u8>, // Fiscal quarter for 10-Q sections
+    pub period_end: Option<String>, // Period end date of the report
+    pub confidence: f64,       // 0-1, see `quality::assess`
+    pub quality_flags: Vec<QualityFlag>,
     // Add fields for XBRL later if needed
     // pub xbrl_facts: Vec<XbrlFact>,
 }
 
+impl ExtractedSection {
+    /// Builds the result for `spec` extracted from `filing`.
+    pub fn new(spec: &SectionSpec, filing: &FilingContext, content_html: String) -> Self {
+        Self {
+            section_name: spec.name.clone(),
+            // TODO: Try to extract a better title from the start element/line text
+            section_title: spec.title.clone(),
+            content_html,
+            filing_year: filing.filing_year,
+            company_name: filing.company_name.clone(),
+            ticker: filing.ticker.clone(),
+            form_type: filing.form_type.clone(),
+            accession_number: filing.accession_number.clone(),
+            is_amendment: filing.is_amendment,
+            fiscal_quarter: filing.fiscal_quarter,
+            period_end: filing.period_end.clone(),
+            confidence: 1.0,
+            quality_flags: Vec::new(),
+        }
+    }
+
+    /// Sets the confidence and quality flags from the content and how it was found.
+    pub fn assessed(mut self, spec: &SectionSpec, evidence: &Evidence) -> Self {
+        (self.confidence, self.quality_flags) = quality::assess(&self, spec, evidence);
+        if !self.quality_flags.is_empty() {
+            tracing::debug!("{} quality flags: {:?} (confidence {:.2})", self.section_name, self.quality_flags, self.confidence);
+        }
+        self
+    }
+}
+
+/// How much a candidate heading element looks like a real heading.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct HeadingFeatures {
+    /// `h1`-`h6`, or wrapped in one
+    pub heading_tag: bool,
+    /// `b`/`strong` or a bold font weight, on the element or a wrapper with the same text
+    pub bold: bool,
+    /// Written in capitals
+    pub caps: bool,
+    /// Short text opening with the match, as opposed to a sentence such as
+    /// "see Item 8. Financial Statements" (a cross-reference)
+    pub standalone: bool,
+}
+
+impl HeadingFeatures {
+    fn of(element: ElementRef, patterns: &[Regex]) -> Self {
+        let text = clean_text(element);
+        let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+        let uppercase = letters.iter().filter(|c| c.is_uppercase()).count();
+        // Only a part/item label such as "PART II -" may precede the match
+        let standalone = text.chars().count() <= MAX_STANDALONE_CHARS
+            && patterns.iter().filter_map(|re| re.find(&text)).any(|m| !text[..m.start()].chars().any(char::is_lowercase));
+        let wrappers = same_text_wrappers(element);
+        Self {
+            heading_tag: wrappers.iter().any(|e| matches!(e.value().name(), "h1" | "h2" | "h3" | "h4" | "h5" | "h6")),
+            bold: wrappers.iter().any(|e| is_bold(*e))
+                || element.descendants().filter_map(ElementRef::wrap).any(is_bold),
+            caps: letters.len() >= 4 && uppercase * 5 >= letters.len() * 4,
+            standalone,
+        }
+    }
+
+    pub fn score(&self) -> f64 {
+        let mut score = 0.0;
+        if self.heading_tag { score += SCORE_HEADING_TAG; }
+        if self.bold { score += SCORE_BOLD; }
+        if self.caps { score += SCORE_CAPS; }
+        score + if self.standalone { SCORE_STANDALONE } else { SCORE_CROSS_REFERENCE }
+    }
+}
+
+/// A start/end pair considered by the text-matching search for a section, with the features
+/// it was scored on. The best-scoring pair with an end is extracted.
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateScore {
+    pub section: String,
+    /// Tag and text of the start element
+    pub start: String,
+    /// Tag and text of the end marker, if one was found
+    pub end: Option<String>,
+    /// Position of the start in the document, from 0 (top) to 1 (bottom)
+    pub position: f64,
+    pub start_features: HeadingFeatures,
+    pub end_features: Option<HeadingFeatures>,
+    /// Earlier end markers passed over to reach `end`
+    pub skipped_ends: usize,
+    /// Text characters (whitespace excluded) from the start to the next item (its first end
+    /// marker), so that the section's content doesn't favour ends further down
+    pub chars: usize,
+    /// Tables up to the next item
+    pub tables: usize,
+    /// References to items ("Item 7A") up to the next item
+    pub item_mentions: usize,
+    /// Item references per 1,000 characters, high in a ToC
+    pub item_density: f64,
+    pub score: f64,
+}
+
+impl CandidateScore {
+    /// `stats` are the start's `section_stats` up to its first end marker.
+    fn new(spec: &SectionSpec, start: ElementRef, end: Option<(usize, ElementRef)>, position: f64, stats: (usize, usize, usize)) -> Self {
+        let (chars, tables, mentions) = stats;
+        let mut score = Self {
+            section: spec.name.clone(),
+            start: describe(start),
+            end: end.map(|(_, end)| describe(end)),
+            position,
+            start_features: HeadingFeatures::of(start, &spec.start_patterns),
+            end_features: end.map(|(_, end)| HeadingFeatures::of(end, &spec.end_patterns)),
+            skipped_ends: end.map_or(0, |(skipped, _)| skipped),
+            chars,
+            tables,
+            item_mentions: mentions,
+            item_density: if chars == 0 { 0.0 } else { mentions as f64 * 1000.0 / chars as f64 },
+            score: 0.0,
+        };
+        score.score = score.total();
+        score
+    }
+
+    fn total(&self) -> f64 {
+        let mut score = self.start_features.score();
+        score += match &self.end_features {
+            Some(end) => END_HEADING_WEIGHT * end.score() + SCORE_SKIPPED_END * self.skipped_ends as f64,
+            None => SCORE_NO_END,
+        };
+        if self.position < EARLY_POSITION { score += SCORE_EARLY_POSITION; }
+        score += match self.chars {
+            0 => SCORE_EMPTY_SECTION,
+            chars if chars < SHORT_SECTION_CHARS => SCORE_SHORT_SECTION,
+            chars => (SCORE_LENGTH * (chars as f64).log10()).min(MAX_LENGTH_SCORE),
+        };
+        if self.tables > 0 { score += SCORE_TABLES; }
+        if is_toc_like(self.item_mentions, self.chars) { score += SCORE_TOC_DENSITY; }
+        score
+    }
+}
+
 // --- Main Extractor Structure (Refactored) ---
 pub struct DomExtractor; // Renamed for clarity
 
 impl DomExtractor {
     pub fn new() -> Self { Self {} }
 
-    /// Extracts Item 8 content using DOM traversal and text matching.
-    pub fn extract_item_8(
+    /// Extracts the section described by `spec` using DOM traversal and text matching.
+    pub fn extract_section(
         &self,
         html_content: &str,
-        filing_year: u32,
-        company_name: &str,
-        ticker: &str,
+        spec: &SectionSpec,
+        filing: &FilingContext,
         min_section_size: usize,
     ) -> Result<ExtractedSection, ExtractError> {
-        tracing::info!("Attempting DOM-based extraction for Item 8: {} ({}), min size {}", ticker, filing_year, min_section_size);
+        let (ticker, filing_year, section_name) = (&filing.ticker, filing.filing_year, &spec.name);
+        let min_section_size = spec.min_size.unwrap_or(min_section_size);
+        tracing::info!("Attempting DOM-based extraction for {}: {} ({}), min size {}", section_name, ticker, filing_year, min_section_size);
 
         // 1. Parse the HTML document
         let document = Html::parse_document(html_content);
 
-        // 2. Find the start and end element boundaries for Item 8
-        let (start_element, end_element) = self.find_section_boundaries(&document, "Item 8", &ITEM_8_START_TEXT_RE, &ITEM_8_END_TEXT_RE)
-            .ok_or_else(|| ExtractError::SectionNotFound(format!("Could not find valid start/end boundaries for Item 8 in DOM for {}-{}", ticker, filing_year)))?;
+        // 2. Find the start and end element boundaries for the section
+        let (start_element, end_element, evidence) = self.find_section_boundaries(&document, spec)
+            .ok_or_else(|| ExtractError::SectionNotFound(format!("Could not find valid start/end boundaries for {} in DOM for {}-{}", section_name, ticker, filing_year)))?;
 
-        tracing::debug!("Found potential Item 8 start element: {:?}", start_element.value().name());
-        tracing::debug!("Found potential Item 8 end marker element: {:?}", end_element.value().name());
+        tracing::debug!("Found potential {} start element: {:?}", section_name, start_element.value().name());
+        tracing::debug!("Found potential {} end marker element: {:?}", section_name, end_element.value().name());
 
         // 3. Extract the HTML content between the identified elements
         let section_html = self.extract_html_between(start_element, end_element)?;
@@ -123,8 +571,8 @@ impl DomExtractor {
 
         // 4. Basic Validation (Size Check)
         if section_size < min_section_size {
-            tracing::error!("Extracted Item 8 DOM section is too small ({} bytes, required {}) for ticker {} ({}).", section_size, min_section_size, ticker, filing_year);
-            return Err(ExtractError::SectionNotFound(format!("Item 8 found but size {} bytes is less than minimum {} bytes", section_size, min_section_size)));
+            tracing::error!("Extracted {} DOM section is too small ({} bytes, required {}) for ticker {} ({}).", section_name, section_size, min_section_size, ticker, filing_year);
+            return Err(ExtractError::SectionNotFound(format!("{} found but size {} bytes is less than minimum {} bytes", section_name, section_size, min_section_size)));
         }
 
         // 5. (Optional but Recommended) Final Content Validation
@@ -132,116 +580,300 @@ impl DomExtractor {
         //    Example: if !self.validate_financial_content_dom(&section_html) { ... return Err ... }
 
 
-        tracing::info!("Successfully extracted Item 8 via DOM for {} ({}): {} bytes", ticker, filing_year, section_size);
-        Ok(ExtractedSection {
-            section_name: "Item 8".to_string(),
-            // TODO: Try to extract a better title from the start_element text
-            section_title: "Financial Statements and Supplementary Data".to_string(),
-            content_html: section_html,
-            filing_year,
-            company_name: company_name.to_string(),
-            ticker: ticker.to_string(),
-        })
+        tracing::info!("Successfully extracted {} via DOM for {} ({}): {} bytes", section_name, ticker, filing_year, section_size);
+        let evidence = Evidence { document_len: html_content.len(), ..evidence };
+        Ok(ExtractedSection::new(spec, filing, section_html).assessed(spec, &evidence))
     }
 
-    /// Finds the start and end ElementRefs for a named section.
-    /// Searches for potential headers, validates text, checks ToC, finds end marker.
+    /// Splits a filing into every section of `specs` it contains, parsing the document once.
+    /// Sections listed in a linked ToC start at the heading their link points at and end at the
+    /// heading of the next ToC entry naming a later item; the others take their best-scoring start/end pair among the
+    /// matching headings (all found in a single pass), or else end at the next section start found. Ending at an item other than the expected `successor`
+    /// (an omitted item) is logged. Sections that can't be found or are smaller than `min_section_size` are left out.
+    pub fn extract_all_sections(
+        &self,
+        html_content: &str,
+        specs: &[SectionSpec],
+        filing: &FilingContext,
+        min_section_size: usize,
+    ) -> Vec<ExtractedSection> {
+        tracing::info!("Attempting DOM-based extraction of {} sections for {} ({})", specs.len(), filing.ticker, filing.filing_year);
+        let document = Html::parse_document(html_content);
+        let toc = self.harvest_toc_links(&document);
+        let linked: Vec<Option<usize>> = specs.iter().map(|spec| toc.iter().position(|link| link.matches(spec))).collect();
+        let unlinked: Vec<&SectionSpec> = specs.iter().zip(&linked).filter(|(_, link)| link.is_none()).map(|(spec, _)| spec).collect();
+        let (candidates, total) = self.find_start_candidates(&document, &unlinked);
+        let mut ranked = unlinked.iter().zip(&candidates)
+            .map(|(spec, starts)| {
+                let ranked = self.rank_boundaries(starts, total, spec);
+                let evidence = ranked.first().map(|best| text_evidence(&ranked, best));
+                ranked.into_iter().next().zip(evidence)
+            });
+        let toc_evidence = Evidence { method: BoundaryMethod::TocLink, ..Evidence::default() };
+        let bounds: Vec<_> = specs.iter().zip(&linked)
+            .map(|(spec, link)| match link {
+                Some(index) => {
+                    let target = toc[*index].target;
+                    let end = self.linked_end(&toc, *index, spec).or_else(|| self.find_end_element(target, spec, None));
+                    Some((toc[*index].position, target, end, toc_evidence))
+                }
+                None => ranked.next().flatten().map(|(best, evidence)| (best.position, best.start, best.end, evidence)),
+            })
+            .collect();
+
+        let mut sections = Vec::new();
+        for (spec, bound) in specs.iter().zip(&bounds) {
+            let Some((position, start_element, end, mut evidence)) = *bound else {
+                tracing::debug!("{} not found for {} ({})", spec.name, filing.ticker, filing.filing_year);
+                continue;
+            };
+            let end_element = match end {
+                Some(element) => element,
+                // Fall back to the nearest later section start
+                None => match bounds.iter().flatten()
+                    .filter(|(pos, element, _, _)| *pos > position && !is_descendant(*element, start_element))
+                    .min_by_key(|(pos, _, _, _)| *pos)
+                {
+                    Some((_, element, _, _)) => {
+                        evidence.fallback_end = true;
+                        *element
+                    }
+                    None => {
+                        tracing::warn!("No end marker found for '{}' after start element.", spec.name);
+                        continue;
+                    }
+                },
+            };
+            let next = specs.iter().zip(&bounds)
+                .find(|(_, next)| next.is_some_and(|(_, element, _, _)| element.id() == end_element.id()));
+            if let Some((next_spec, _)) = next.filter(|(next_spec, _)| spec.successor.as_deref() != Some(next_spec.name.as_str())) {
+                evidence.unexpected_successor = true;
+                tracing::debug!("{} is followed by {} (expected {})", spec.name, next_spec.name, spec.successor.as_deref().unwrap_or("none"));
+            }
+
+            let min_size = spec.min_size.unwrap_or(min_section_size);
+            match self.extract_html_between(start_element, end_element) {
+                Ok(html) if html.len() >= min_size => {
+                    tracing::info!("Extracted {} via DOM for {} ({}): {} bytes", spec.name, filing.ticker, filing.filing_year, html.len());
+                    evidence.document_len = html_content.len();
+                    sections.push(ExtractedSection::new(spec, filing, html).assessed(spec, &evidence));
+                }
+                Ok(html) => tracing::debug!("Skipping {}: {} bytes is less than minimum {} bytes", spec.name, html.len(), min_size),
+                Err(e) => tracing::warn!("Could not extract {}: {}", spec.name, e),
+            }
+        }
+        sections
+    }
+
+    /// Finds the start and end ElementRefs for a section, with the evidence for them.
+    /// Follows the section's entry in a linked ToC if there is one; otherwise scores every
+    /// start/end pair found by text matching outside the ToC and takes the best one with an end.
     fn find_section_boundaries<'a>(
         &self,
         document: &'a Html,
-        section_name: &str, // e.g., "Item 8"
-        start_patterns: &[Regex],
-        end_patterns: &[Regex],
-    ) -> Option<(ElementRef<'a>, ElementRef<'a>)> {
+        spec: &SectionSpec,
+    ) -> Option<(ElementRef<'a>, ElementRef<'a>, Evidence)> {
+        let toc = self.harvest_toc_links(document);
+        if let Some(index) = toc.iter().position(|link| link.matches(spec)) {
+            let start_element = toc[index].target;
+            tracing::info!("Following ToC link for {} to {:?} '{}'", spec.name, start_element.value().name(), clean_text(start_element));
+            match self.linked_end(&toc, index, spec).or_else(|| self.find_end_element(start_element, spec, None)) {
+                Some(end_element) => {
+                    return Some((start_element, end_element, Evidence { method: BoundaryMethod::TocLink, ..Evidence::default() }));
+                }
+                None => tracing::debug!("No end found after the ToC target of {}, falling back to text matching", spec.name),
+            }
+        }
 
-        let mut best_start_element: Option<ElementRef> = None;
+        // Otherwise the best-scoring start/end pair among the text matches
+        let (candidates, total) = self.find_start_candidates(document, &[spec]);
+        let ranked = self.rank_boundaries(&candidates[0], total, spec);
+        let Some(best) = ranked.iter().find(|boundary| boundary.end.is_some()) else {
+            if !ranked.is_empty() {
+                tracing::warn!("No end marker found for '{}' after any of its {} start candidates.", spec.name, candidates[0].len());
+            }
+            return None;
+        };
+        tracing::info!("Selected {} start {} (score {:.2}, {} candidates)", spec.name, best.score.start, best.score.score, candidates[0].len());
+        if best.position != candidates[0][0].0 {
+            tracing::info!("Passed over the first match for {}: {}", spec.name, describe(candidates[0][0].1));
+        }
+        Some((best.start, best.end?, text_evidence(&ranked, best)))
+    }
 
-        // Iterate through potential header elements defined by the selector
-        for element in document.select(&POTENTIAL_HEADER_SELECTOR) {
-            let element_text = element.text().collect::<String>();
-            let cleaned_text = element_text
-                .trim()
-                .replace("\n", " ")
-                .replace("&nbsp;", " ")
-                .replace("&#160;", " ");
+    /// Finds the start candidates of each spec in a single pass over the document's elements:
+    /// elements matching its header selector and one of its start patterns that aren't part of
+    /// the ToC, each narrowed to the innermost match within it (a wrapper's text contains its
+    /// heading's text). Returns, per spec, up to `MAX_START_CANDIDATES` elements with their
+    /// position in document order, and the number of elements in the document.
+    fn find_start_candidates<'a>(&self, document: &'a Html, specs: &[&SectionSpec]) -> (Vec<Vec<(usize, ElementRef<'a>)>>, usize) {
+        let mut candidates: Vec<Vec<(usize, ElementRef)>> = vec![Vec::new(); specs.len()];
+        // Latest match per spec, replaced by deeper matches until the search leaves its subtree
+        let mut current: Vec<Option<(usize, ElementRef)>> = vec![None; specs.len()];
+        let mut total = 0;
 
-            // Check if element text matches any start patterns
-            if start_patterns.iter().any(|re| re.is_match(&cleaned_text)) {
-                tracing::trace!("Found potential '{}' start element: '{}' (text: '{}')", section_name, element.value().name(), cleaned_text);
+        for (position, element) in document.root_element().descendants().filter_map(ElementRef::wrap).enumerate() {
+            total = position + 1;
+            for (current, candidates) in current.iter_mut().zip(candidates.iter_mut()) {
+                if current.is_some_and(|(_, start)| !is_descendant(element, start)) {
+                    candidates.extend(current.take());
+                }
+            }
+            let is_header = POTENTIAL_HEADER_SELECTOR.matches(&element);
+            let mut cleaned_text = None; // Computed once, for the first spec considering the element
+            let mut in_toc = None; // Checked once, on the first match
+
+            for ((spec, current), candidates) in specs.iter().zip(current.iter_mut()).zip(&candidates) {
+                let candidate = match &spec.selector {
+                    Some(selector) => selector.matches(&element),
+                    None => is_header,
+                };
+                if !candidate || candidates.len() >= MAX_START_CANDIDATES {
+                    continue;
+                }
+                // Check if element text matches any start patterns
+                let cleaned_text = cleaned_text.get_or_insert_with(|| clean_text(element));
+                if !spec.start_patterns.iter().any(|re| re.is_match(cleaned_text)) {
+                    continue;
+                }
+                tracing::trace!("Found potential '{}' start element: '{}' (text: '{}')", spec.name, element.value().name(), cleaned_text);
 
                 // ** Crucial Check: Is this element likely part of the Table of Contents? **
-                if self.is_in_toc_dom(element) {
+                if *in_toc.get_or_insert_with(|| self.is_in_toc_dom(element)) {
                     tracing::debug!("Skipping potential start element - likely in ToC: '{}'", cleaned_text);
                     continue; // Skip this element, it's probably in the ToC
                 }
+                *current = Some((position, element));
+            }
+        }
+        for (current, candidates) in current.into_iter().zip(candidates.iter_mut()) {
+            candidates.extend(current);
+        }
+        (candidates, total)
+    }
 
-                // ** Basic Content Lookahead (Optional but helpful) **
-                // Simple check: does the *immediate* next content look promising?
-                // (e.g., a table, or text containing keywords) - This is a simpler validation than the old byte-based one.
-                // if !self.peek_ahead_for_content(element) {
-                //     tracing::debug!("Skipping potential start - lookahead check failed for: '{}'", cleaned_text);
-                //     continue;
-                // }
-
-                // If we passed the ToC check (and optionally lookahead), this is our candidate start
-                // We take the *first* valid one found based on document order.
-                best_start_element = Some(element);
-                tracing::info!("Selected candidate start element for {}: {:?} '{}'", section_name, element.value().name(), cleaned_text);
-                break; // Stop searching for start markers
+    /// Pairs each start candidate with the first `MAX_END_CANDIDATES` end markers after it and
+    /// scores every pair (see `CandidateScore`), best first. A start without any end marker is
+    /// kept as a pair without an end, for callers that have another way to end the section.
+    fn rank_boundaries<'a>(&self, starts: &[(usize, ElementRef<'a>)], total: usize, spec: &SectionSpec) -> Vec<Boundary<'a>> {
+        let mut ranked = Vec::new();
+        for &(position, start) in starts {
+            let relative = position as f64 / total.max(1) as f64;
+            let ends = self.end_candidates(start, spec, None, MAX_END_CANDIDATES);
+            let stats = section_stats(start, ends.first().copied());
+            if ends.is_empty() {
+                ranked.push(Boundary { start, end: None, position, score: CandidateScore::new(spec, start, None, relative, stats) });
             }
+            for (skipped, end) in ends.into_iter().enumerate() {
+                let score = CandidateScore::new(spec, start, Some((skipped, end)), relative, stats);
+                ranked.push(Boundary { start, end: Some(end), position, score });
+            }
+        }
+        // Stable: ties go to the earlier start and end
+        ranked.sort_by(|a, b| b.score.score.total_cmp(&a.score.score));
+        for boundary in &ranked {
+            tracing::debug!("{} candidate scored {:.2}: {:?}", spec.name, boundary.score.score, boundary.score);
         }
+        ranked
+    }
+
+    /// Scores of the start/end pairs the text-matching search considers for each of `specs`,
+    /// best first per section, for debugging boundary choices. Sections followed through a
+    /// linked ToC don't use these.
+    pub fn boundary_scores(&self, html_content: &str, specs: &[SectionSpec]) -> Vec<CandidateScore> {
+        let document = Html::parse_document(html_content);
+        let specs: Vec<&SectionSpec> = specs.iter().collect();
+        let (candidates, total) = self.find_start_candidates(&document, &specs);
+        specs.iter().zip(&candidates)
+            .flat_map(|(spec, starts)| self.rank_boundaries(starts, total, spec))
+            .map(|boundary| boundary.score)
+            .collect()
+    }
 
-        // If no valid start element found, return None
-        let start_element = best_start_element?;
-        tracing::debug!("Confirmed start element for {}: {:?}", section_name, start_element.id());
-
-
-        // --- Find the End Marker ---
-        // Search *after* the start element for the *first* element matching end patterns.
-        let mut potential_end_element: Option<ElementRef> = None;
-        for element in start_element.next_siblings().flat_map(|node| ElementRef::wrap(node)) {
-             // Recursively check descendants as well? Maybe too complex for now.
-             // Let's first check the direct siblings and their header-like children.
-            for descendant in element.select(&POTENTIAL_HEADER_SELECTOR) { // Check headers within siblings
-                 let descendant_text = descendant.text().collect::<String>();
-                 let cleaned_text = descendant_text.trim().replace("\n", " ").replace("&nbsp;", " ");
-
-                 if end_patterns.iter().any(|re| re.is_match(&cleaned_text)) {
-                     // Found a potential end marker
-                     tracing::debug!("Found potential end marker for '{}' after start: {:?} '{}'", section_name, descendant.value().name(), cleaned_text);
-                     potential_end_element = Some(descendant);
-                     break; // Found the first end marker, stop searching this branch
-                 }
+    /// Harvests the internal links of the document (`<a href="#id">`), resolving each fragment to
+    /// the element with that `id` (or the `<a>` with that `name`) and then to the heading it marks.
+    /// Only forward links count, which leaves out "back to contents" links; consecutive links
+    /// to the same heading (item number, title and page number of one ToC row) are merged.
+    fn harvest_toc_links<'a>(&self, document: &'a Html) -> Vec<TocLink<'a>> {
+        let mut positions = HashMap::new();
+        let mut anchors: HashMap<&'a str, ElementRef<'a>> = HashMap::new();
+        let mut links = Vec::new();
+        for (position, element) in document.root_element().descendants().filter_map(ElementRef::wrap).enumerate() {
+            positions.insert(element.id(), position);
+            let value = element.value();
+            let name = if value.name() == "a" { value.attr("name") } else { None };
+            for key in [value.id(), name].into_iter().flatten() {
+                anchors.entry(key).or_insert(element);
+            }
+            if INTERNAL_LINK_SELECTOR.matches(&element) {
+                links.push((position, element));
             }
-             if potential_end_element.is_some() { break; } // Stop searching siblings if end found
-
-             // Also check the top-level sibling itself if it's a header
-             if let Some(name) = element.value().name().to_lowercase().split('.').next() {
-                 if ["h1","h2","h3","h4","h5","h6","p","div","font"].contains(&name) { // Check common structural/header tags
-                     let element_text = element.text().collect::<String>();
-                     let cleaned_text = element_text.trim().replace("\n", " ").replace("&nbsp;", " ");
-                      if end_patterns.iter().any(|re| re.is_match(&cleaned_text)) {
-                         tracing::debug!("Found potential end marker (sibling) for '{}' after start: {:?} '{}'", section_name, element.value().name(), cleaned_text);
-                         potential_end_element = Some(element);
-                         break; // Found the first end marker, stop searching siblings
-                     }
-                 }
-             }
-              if potential_end_element.is_some() { break; } // Stop searching siblings if end found
         }
 
+        let mut toc: Vec<TocLink> = Vec::new();
+        for (link_position, link) in links {
+            let fragment = link.value().attr("href").unwrap_or_default().trim().trim_start_matches('#');
+            let Some(&anchor) = anchors.get(fragment) else {
+                continue;
+            };
+            let target = anchor_heading(anchor);
+            let position = positions[&target.id()];
+            if position <= link_position {
+                continue; // Back-link, or a link inside its own target
+            }
+            let labels = link_labels(link);
+            match toc.last_mut() {
+                Some(last) if last.target.id() == target.id() => {
+                    for label in labels {
+                        if !last.labels.contains(&label) {
+                            last.labels.push(label);
+                        }
+                    }
+                }
+                _ => toc.push(TocLink { labels, target, position }),
+            }
+        }
+        tracing::debug!("Found {} ToC links with resolvable targets", toc.len());
+        toc
+    }
 
-        // TODO: Handle case where no end marker is found more gracefully
-        // Maybe search until end of document or use a fallback size limit?
-        let end_element = potential_end_element.or_else(|| {
-             tracing::warn!("No specific end marker found for '{}' after start element. Finding end of document may be needed.", section_name);
-             // Placeholder: Need a better way to find the "end" if no marker exists
-             // For now, maybe just return None which causes the main function to error out.
-             None
-         })?;
+    /// End of the section started by ToC entry `index`: the target of the next entry further down
+    /// the document (outside the start heading) that names an end of the spec, or an end marker
+    /// of the spec found before it, such as a Part heading between the last item of one part and
+    /// the first of the next. Entries in between, such as the statements listed under Item 8,
+    /// are sub-entries of the section. `None` if no later entry ends the section.
+    fn linked_end<'a>(&self, toc: &[TocLink<'a>], index: usize, spec: &SectionSpec) -> Option<ElementRef<'a>> {
+        let (start, position) = (toc[index].target, toc[index].position);
+        let next = toc[index + 1..].iter()
+            .find(|link| link.position > position && !is_descendant(link.target, start) && link.ends(spec))?
+            .target;
+        // A marker wrapping the next target would cut off the end of the section
+        match self.find_end_element(start, spec, Some(next)) {
+            Some(marker) if !is_descendant(next, marker) => Some(marker),
+            _ => Some(next),
+        }
+    }
 
+    /// Searches *after* the start element, in document order across the whole tree, for the
+    /// *first* element matching the end patterns (see `end_candidates`), stopping at `limit` if given.
+    fn find_end_element<'a>(&self, start_element: ElementRef<'a>, spec: &SectionSpec, limit: Option<ElementRef<'a>>) -> Option<ElementRef<'a>> {
+        let end_element = self.end_candidates(start_element, spec, limit, 1).pop()?;
+        tracing::debug!("Found potential end marker for '{}' after start: {:?} '{}'", spec.name, end_element.value().name(), clean_text(end_element));
+        Some(end_element)
+    }
 
-        Some((start_element, end_element))
+    /// The first `max` elements after the start element matching the end patterns, each narrowed
+    /// to the innermost match (see `innermost_matches`), stopping at `limit` if given.
+    fn end_candidates<'a>(&self, start_element: ElementRef<'a>, spec: &SectionSpec, limit: Option<ElementRef<'a>>, max: usize) -> Vec<ElementRef<'a>> {
+        let is_end = |element: &ElementRef| {
+            // Paragraphs too, unless the spec has its own selector: plain `<p>ITEM 9. ...</p>`
+            // headings are common in older filings
+            let candidate = spec.header_selector().matches(element)
+                || (spec.selector.is_none() && element.value().name() == "p");
+            candidate && spec.end_patterns.iter().any(|re| re.is_match(&clean_text(*element)))
+        };
+        let elements = elements_after(start_element).take_while(|element| limit.is_none_or(|limit| element.id() != limit.id()));
+        innermost_matches(elements, is_end, max)
     }
 
 
@@ -255,6 +887,12 @@ impl DomExtractor {
              return true;
         }
     
+        // Check 1b: Is the element itself a ToC container? (e.g. a <div class="toc"> matched by the header selector)
+        if TOC_CONTAINER_SELECTOR.matches(&element) {
+            tracing::debug!("Element itself matches TOC_CONTAINER_SELECTOR, confirmed ToC.");
+            return true;
+        }
+
         // Check 2: Traverse ancestors looking for clues
         let mut table_ancestor_found = false; // Flag to check context
         for ancestor_node in element.ancestors() {
@@ -309,39 +947,35 @@ impl DomExtractor {
         false // Default: Assume not in ToC if no checks match
     }
 
-    /// Extracts the raw HTML string for all nodes between start_el (exclusive) and end_el (exclusive).
+    /// Extracts the HTML for everything between start_el (exclusive, with its contents) and
+    /// end_el (exclusive) in document order. The two may sit at different depths: ancestors of
+    /// either one below their common ancestor are reopened around the part of them that lies
+    /// inside the section, so the result is a well-formed fragment.
     fn extract_html_between<'a>(
         &self,
         start_el: ElementRef<'a>,
         end_el: ElementRef<'a>,
     ) -> Result<String, ExtractError> {
-        let mut content = String::new();
-
-        // Iterate over nodes that are siblings *after* the start_el node.
-        // start_el.next_siblings() returns an iterator over ego_tree::NodeRef<'a, Node>.
-        for node in start_el.next_siblings() { // <<< Use the next_siblings() iterator directly
-
-            // Check if the current node's ID is the same as the end element's ID
-            if node.id() == end_el.id() { // <<< Compare node IDs directly
-                break; // Stop when we reach the end element's node
-            }
-
-            // Append the HTML representation of the node
-            // ElementRef::wrap takes a NodeRef, which 'node' already is.
-            if let Some(el_ref) = ElementRef::wrap(node) {
-                content.push_str(&el_ref.html());
-            } else {
-                // Handle other node types, primarily Text nodes
-                match node.value() {
-                    Node::Text(text_node) => {
-                        // Escape if needed for rendering, direct text for raw extraction
-                        content.push_str(&text_node.text);
-                    }
-                    _ => {} // Ignore comments, etc.
-                }
-            }
+        // Paths from the document root down to each element
+        let path = |element: ElementRef<'a>| -> Vec<NodeRef<'a, Node>> {
+            let mut path: Vec<NodeRef<Node>> = element.ancestors().collect();
+            path.reverse();
+            path.push(*element);
+            path
+        };
+        let (start_path, end_path) = (path(start_el), path(end_el));
+        let common = start_path.iter().zip(&end_path).take_while(|(a, b)| a.id() == b.id()).count();
+        if common == 0 || common == start_path.len() || common == end_path.len() {
+            return Err(ExtractError::HtmlParseError("Section start and end are nested in one another".to_string()));
         }
 
+        // Rest of the start element's ancestors, the common ancestor's children in between,
+        // and the beginning of the end element's ancestors
+        let mut content = html_after(&start_path[common..]);
+        for node in start_path[common].next_siblings().take_while(|node| node.id() != end_path[common].id()) {
+            content.push_str(&node_html(node));
+        }
+        content.push_str(&html_before(&end_path[common..]));
         Ok(content)
     }
 
@@ -353,6 +987,245 @@ impl DomExtractor {
 
 }
 
+/// Element text on one line, with leftover non-breaking space entities replaced.
+fn clean_text(element: ElementRef) -> String {
+    element.text().collect::<String>()
+        .trim()
+        .replace("\n", " ")
+        .replace("&nbsp;", " ")
+        .replace("&#160;", " ")
+}
+
+/// Elements after `element` in document order, excluding its descendants.
+fn elements_after<'a>(element: ElementRef<'a>) -> impl Iterator<Item = ElementRef<'a>> {
+    std::iter::successors(Some(*element), |node| node.parent())
+        .flat_map(|node| node.next_siblings())
+        .flat_map(|sibling| sibling.descendants())
+        .filter_map(ElementRef::wrap)
+}
+
+fn is_descendant(element: ElementRef, ancestor: ElementRef) -> bool {
+    element.ancestors().any(|node| node.id() == ancestor.id())
+}
+
+/// The first `max` elements (in document order) satisfying `matches`, each narrowed to the
+/// innermost of its descendants that match too: a wrapper `<div>` whose text happens to contain
+/// a heading stands for that heading, not for everything it wraps.
+fn innermost_matches<'a>(
+    elements: impl Iterator<Item = ElementRef<'a>>,
+    matches: impl Fn(&ElementRef<'a>) -> bool,
+    max: usize,
+) -> Vec<ElementRef<'a>> {
+    let mut found = Vec::new();
+    let mut current: Option<ElementRef> = None;
+    for element in elements {
+        if let Some(previous) = current.filter(|previous| !is_descendant(element, *previous)) {
+            found.push(previous);
+            current = None;
+            if found.len() == max {
+                return found;
+            }
+        }
+        if matches(&element) {
+            current = Some(element);
+        }
+    }
+    found.extend(current);
+    found
+}
+
+/// Serialized node: elements with their contents, text escaped; comments and the like are dropped.
+fn node_html(node: NodeRef<Node>) -> String {
+    match ElementRef::wrap(node) {
+        Some(element) => element.html(),
+        None => match node.value() {
+            Node::Text(text) => escape_html(&text.text),
+            _ => String::new(),
+        },
+    }
+}
+
+/// `inner` wrapped in a copy of `node`'s tags (left out if there is nothing inside).
+fn reopen(node: NodeRef<Node>, inner: String) -> String {
+    let Some(element) = ElementRef::wrap(node).filter(|_| !inner.trim().is_empty()) else {
+        return inner;
+    };
+    let name = element.value().name();
+    let attrs: String = element.value().attrs()
+        .map(|(key, value)| format!(" {}=\"{}\"", key, escape_html(value).replace('"', "&quot;")))
+        .collect();
+    format!("<{}{}>{}</{}>", name, attrs, inner, name)
+}
+
+/// What follows the last node of `path` inside each of the other nodes of the path
+/// (`path` runs down from the common ancestor's child to the start element).
+fn html_after(path: &[NodeRef<Node>]) -> String {
+    match path {
+        [] | [_] => String::new(),
+        [node, rest @ ..] => {
+            let mut inner = html_after(rest);
+            for sibling in rest[0].next_siblings() {
+                inner.push_str(&node_html(sibling));
+            }
+            reopen(*node, inner)
+        }
+    }
+}
+
+/// What precedes the last node of `path` inside each of the other nodes of the path
+/// (`path` runs down from the common ancestor's child to the end element).
+fn html_before(path: &[NodeRef<Node>]) -> String {
+    match path {
+        [] | [_] => String::new(),
+        [node, rest @ ..] => {
+            let mut siblings: Vec<NodeRef<Node>> = rest[0].prev_siblings().collect();
+            siblings.reverse();
+            let mut inner: String = siblings.into_iter().map(node_html).collect();
+            inner.push_str(&html_before(rest));
+            reopen(*node, inner)
+        }
+    }
+}
+
+/// Whether `mentions` item references in `chars` characters of text read like a ToC.
+pub(crate) fn is_toc_like(mentions: usize, chars: usize) -> bool {
+    mentions >= MIN_TOC_ITEMS && mentions as f64 * 1000.0 > TOC_ITEM_DENSITY * chars as f64
+}
+
+/// Tag and (shortened) text of an element, for logs and candidate scores.
+fn describe(element: ElementRef) -> String {
+    let text = clean_text(element);
+    let short: String = text.chars().take(120).collect();
+    format!("<{}> {}{}", element.value().name(), short, if short.len() < text.len() { "..." } else { "" })
+}
+
+fn is_bold(element: ElementRef) -> bool {
+    matches!(element.value().name(), "b" | "strong")
+        || element.value().attr("style").is_some_and(|style| BOLD_STYLE_RE.is_match(style))
+}
+
+/// The element and its ancestors holding no other text, e.g. the `<p>` and `<font>` around `<b>ITEM 8.</b>`.
+fn same_text_wrappers(element: ElementRef) -> Vec<ElementRef> {
+    let visible = |element: ElementRef, limit: usize| -> usize {
+        element.text().flat_map(str::chars).filter(|c| !c.is_whitespace()).take(limit).count()
+    };
+    let own = visible(element, usize::MAX);
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap)
+            .take_while(|ancestor| visible(*ancestor, own + 1) == own))
+        .collect()
+}
+
+/// Text characters (whitespace excluded), tables and item references between `start` (exclusive)
+/// and `end`, or the end of the document.
+fn section_stats(start: ElementRef, end: Option<ElementRef>) -> (usize, usize, usize) {
+    let (mut chars, mut tables, mut mentions) = (0, 0, 0);
+    let nodes = std::iter::successors(Some(*start), |node| node.parent())
+        .flat_map(|node| node.next_siblings())
+        .flat_map(|sibling| sibling.descendants())
+        .take_while(|node| end.is_none_or(|end| node.id() != end.id()));
+    for node in nodes {
+        match node.value() {
+            Node::Text(text) => {
+                chars += text.chars().filter(|c| !c.is_whitespace()).count();
+                mentions += ITEM_MENTION_RE.find_iter(text).count();
+            }
+            Node::Element(element) if element.name() == "table" => tables += 1,
+            _ => {}
+        }
+    }
+    (chars, tables, mentions)
+}
+
+/// Evidence for `best`, chosen by text matching among the `ranked` pairs: whether its start
+/// reads like a cross-reference, and whether a different start scored almost as well.
+fn text_evidence(ranked: &[Boundary], best: &Boundary) -> Evidence {
+    let runner_up = ranked.iter()
+        .filter(|other| other.end.is_some() && other.start.id() != best.start.id())
+        .map(|other| other.score.score)
+        .fold(f64::NEG_INFINITY, f64::max);
+    Evidence {
+        method: BoundaryMethod::TextMatch,
+        weak_start: !best.score.start_features.standalone,
+        ambiguous_start: best.score.score - runner_up < AMBIGUOUS_MARGIN,
+        ..Evidence::default()
+    }
+}
+
+/// A scored start/end pair for a section found by text matching.
+struct Boundary<'a> {
+    start: ElementRef<'a>,
+    end: Option<ElementRef<'a>>,
+    /// Position of `start` among the document's elements
+    position: usize,
+    score: CandidateScore,
+}
+
+/// A Table of Contents link resolved to the heading it points at.
+#[derive(Debug, Clone)]
+struct TocLink<'a> {
+    /// Link text, and the text of the ToC row holding it
+    labels: Vec<String>,
+    /// Heading the link's fragment resolves to
+    target: ElementRef<'a>,
+    /// Position of `target` among the document's elements
+    position: usize,
+}
+
+impl TocLink<'_> {
+    /// Whether the ToC entry names the section, judged by the section's start patterns.
+    fn matches(&self, spec: &SectionSpec) -> bool {
+        self.labels.iter().any(|label| spec.start_patterns.iter().any(|re| re.is_match(label)))
+    }
+
+    /// Whether the ToC entry, or the heading it points at, matches the section's end patterns
+    /// (for catalogue items, the headings of the later items and parts).
+    fn ends(&self, spec: &SectionSpec) -> bool {
+        let heading = clean_text(self.target);
+        self.labels.iter().chain(std::iter::once(&heading))
+            .any(|text| spec.end_patterns.iter().any(|re| re.is_match(text)))
+    }
+}
+
+/// Texts identifying a ToC link: its own text, and that of its table row or, failing that,
+/// of its parent when the parent holds no other link.
+fn link_labels(link: ElementRef) -> Vec<String> {
+    let spaced = |element: ElementRef| element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut labels = vec![spaced(link)];
+    let container = link.ancestors().filter_map(ElementRef::wrap).find(|ancestor| ancestor.value().name() == "tr")
+        .or_else(|| link.parent().and_then(ElementRef::wrap).filter(|parent| parent.select(&INTERNAL_LINK_SELECTOR).count() == 1));
+    if let Some(container) = container {
+        labels.push(spaced(container));
+    }
+    labels
+}
+
+fn is_heading_sized(element: ElementRef) -> bool {
+    (1..=MAX_HEADING_CHARS).contains(&clean_text(element).chars().count())
+}
+
+/// The heading an anchor marks. Anchors are often empty, either inside the heading
+/// (`<p><a name="x"></a>ITEM 8. ...</p>`) or just before it (`<div id="x"></div><div>Item 8 ...</div>`),
+/// or sit on a wrapper holding the whole section, whose heading is its first child with text.
+fn anchor_heading(anchor: ElementRef) -> ElementRef {
+    let mut element = anchor;
+    if clean_text(element).is_empty() {
+        let parent = element.parent().and_then(ElementRef::wrap)
+            .filter(|parent| parent.value().name() != "body" && is_heading_sized(*parent));
+        match parent.or_else(|| element.next_siblings().filter_map(ElementRef::wrap).find(|next| !clean_text(*next).is_empty())) {
+            Some(heading) => element = heading,
+            None => return anchor,
+        }
+    }
+    while !is_heading_sized(element) {
+        match element.children().filter_map(ElementRef::wrap).find(|child| !clean_text(*child).is_empty()) {
+            Some(child) => element = child,
+            None => break,
+        }
+    }
+    element
+}
+
 // --- Old Helper Functions (To Be Removed or Replaced) ---
 // fn is_in_table_of_contents(...) -> bool { ... REMOVED ... }
 // fn contains_financial_content(...) -> bool { ... REMOVED or ADAPT ... }
@@ -365,6 +1238,19 @@ mod tests {
      // Use a smaller min size for tests unless specifically testing size limits
      const TEST_MIN_SIZE: usize = 50;
 
+     fn test_filing() -> FilingContext {
+         FilingContext {
+             ticker: "TST".to_string(),
+             company_name: "TestCo".to_string(),
+             filing_year: 2023,
+             form_type: "10-K".to_string(),
+             accession_number: "0000000000-23-000001".to_string(),
+             is_amendment: false,
+             fiscal_quarter: None,
+             period_end: Some("2023-12-31".to_string()),
+         }
+     }
+
      #[test]
      fn test_placeholder_dom_extraction() {
          // Create test HTML suitable for DOM parsing
@@ -386,7 +1272,7 @@ mod tests {
          "#;
 
          let extractor = DomExtractor::new();
-         let result = extractor.extract_item_8(html, 2023, "TestCo", "TST", TEST_MIN_SIZE);
+         let result = extractor.extract_section(html, &TEN_K_ITEM_8, &test_filing(), TEST_MIN_SIZE);
 
          assert!(result.is_ok(), "DOM extraction failed: {:?}", result.err());
 
@@ -402,6 +1288,78 @@ mod tests {
          }
      }
 
+     #[test]
+     fn test_boundaries_at_different_depths() {
+         // Headings nested in wrappers, with Item 9 in a different wrapper than Item 8
+         let html = r#"
+             <html><body>
+             <div><div><font><b>Item 8. Financial Statements and Supplementary Data</b></font></div>
+             <div><font>Our audited statements follow.</font></div>
+             <table><tr><td>Total assets</td><td>3,400</td></tr></table></div>
+             <div class="page"><p>More notes & schedules.</p><div><font>Item 9. Changes in and Disagreements with Accountants</font></div><p>None.</p></div>
+             </body></html>
+         "#;
+         let section = DomExtractor::new().extract_section(html, &TEN_K_ITEM_8, &test_filing(), 10).unwrap();
+         let content = &section.content_html;
+         assert!(content.contains("Our audited statements follow."));
+         assert!(content.contains("Total assets"));
+         assert!(content.contains(r#"<div class="page"><p>More notes &amp; schedules.</p></div>"#), "{}", content);
+         assert!(!content.contains("Supplementary Data"));
+         assert!(!content.contains("Item 9"));
+         assert!(!content.contains("None."));
+         // Partial ancestors are closed again
+         assert_eq!(content.matches("<div").count(), content.matches("</div>").count());
+         assert!(content.starts_with("<div>") && content.trim_end().ends_with("</div>"));
+     }
+
+     #[test]
+     fn test_scored_candidates() {
+         // The MD&A cross-reference comes first and would end at the Item 9 heading too
+         let html = r#"
+             <html><body>
+             <h3>Item 7. Management's Discussion and Analysis</h3>
+             <p>Sales rose.</p>
+             <div>For details, see Item 8. Financial Statements and Supplementary Data, Note 3.</div>
+             <p>Liquidity remained strong.</p>
+             <p><b>ITEM 8. FINANCIAL STATEMENTS AND SUPPLEMENTARY DATA</b></p>
+             <table><tr><td>Total assets</td><td>5,120</td></tr></table>
+             <p>Notes to the consolidated financial statements.</p>
+             <p>The exhibits to this report are listed in Item 15.</p>
+             <p><b>ITEM 9. CHANGES IN AND DISAGREEMENTS WITH ACCOUNTANTS</b></p>
+             <p>None.</p>
+             </body></html>
+         "#;
+         let extractor = DomExtractor::new();
+         let section = extractor.extract_section(html, &TEN_K_ITEM_8, &test_filing(), 10).unwrap();
+         assert!(section.content_html.contains("Total assets"));
+         assert!(!section.content_html.contains("Liquidity"), "{}", section.content_html);
+         // "exhibits" in a sentence is a weak end marker, passed over for the Item 9 heading
+         assert!(section.content_html.contains("listed in Item 15"));
+         assert!(!section.content_html.contains("None."));
+         assert!(!section.quality_flags.contains(&QualityFlag::WeakStart));
+         assert!(!section.quality_flags.contains(&QualityFlag::AmbiguousStart));
+
+         let scores = extractor.boundary_scores(html, std::slice::from_ref(&TEN_K_ITEM_8));
+         assert!(scores.len() > 2);
+         let best = &scores[0];
+         assert!(best.start.starts_with("<b> ITEM 8."), "{}", best.start);
+         assert!(best.start_features.bold && best.start_features.caps && best.start_features.standalone);
+         assert_eq!((best.skipped_ends, best.tables), (1, 1));
+         let cross_reference = scores.iter().find(|score| score.start.starts_with("<div>")).unwrap();
+         assert!(!cross_reference.start_features.standalone);
+         assert!(cross_reference.score < best.score);
+     }
+
+     #[test]
+     fn test_toc_container_is_toc() {
+         // `div` is a potential header, so a ToC container whose text mentions Item 8 is a start
+         // candidate itself, not only the entries inside it; previously it was taken as the start
+         let html = r#"<body><div class="toc"><p><b>Item 8. Financial Statements</b>... Page 5</p></div><h2>Item 8. Financial Statements and Supplementary Data</h2></body>"#;
+         let document = Html::parse_document(html);
+         let container = document.select(&Selector::parse("div.toc").unwrap()).next().unwrap();
+         assert!(DomExtractor::new().is_in_toc_dom(container), "A ToC container is part of the ToC");
+     }
+
      #[test]
      fn test_toc_detection_dom() {
          let html_toc = r#"<body><div id="toc"><p><b>Item 8. Financials</b></p></div><hr/><h2>Item 8 Actual</h2></body>"#;
@@ -422,4 +1380,200 @@ mod tests {
          assert!(!extractor.is_in_toc_dom(actual_element_in_toc_doc), "Should NOT detect element after ToC div");
          assert!(!extractor.is_in_toc_dom(actual_element_in_no_toc_doc), "Should NOT detect element when no ToC exists");
      }
-}
\ No newline at end of file
+
+     #[test]
+     fn test_ten_k_all_sections() {
+         let html = r#"
+             <html><body>
+             <div class="toc"><p><b>Item 1. Business</b></p><p><b>Item 7. Management's Discussion and Analysis</b></p></div>
+             <h2>PART I</h2>
+             <h3>Item 1. Business</h3>
+             <p>We make widgets.</p>
+             <div>As discussed in Item 7. Management's Discussion and Analysis, demand grew.</div>
+             <h3>Item 1A. Risk Factors</h3>
+             <p>Widget demand may fall.</p>
+             <h3>ITEM 1C. CYBERSECURITY</h3>
+             <p>We monitor threats.</p>
+             <h2>PART II</h2>
+             <h3>Item 7. Management’s Discussion and Analysis of Financial Condition and Results of Operations</h3>
+             <p>Sales rose.</p>
+             <h3>Item 7A. Quantitative and Qualitative Disclosures About Market Risk</h3>
+             <p>Interest rate risk is low.</p>
+             <h3>Item 8. Financial Statements and Supplementary Data</h3>
+             <table><tr><td>Total assets</td><td>1,234</td></tr></table>
+             <h3>Item 9A. Controls and Procedures</h3>
+             <p>Controls were effective.</p>
+             <h2>PART III</h2>
+             <h3>Item 10. Directors, Executive Officers and Corporate Governance</h3>
+             <p>Incorporated by reference to the proxy statement.</p>
+             <p><b>SIGNATURES</b></p>
+             </body></html>
+         "#;
+         let extractor = DomExtractor::new();
+         let sections = extractor.extract_all_sections(html, &TEN_K_SECTIONS, &test_filing(), 5);
+         let names: Vec<&str> = sections.iter().map(|s| s.section_name.as_str()).collect();
+         assert_eq!(names, ["Item 1", "Item 1A", "Item 1C", "Item 7", "Item 7A", "Item 8", "Item 9A", "Item 10"]);
+
+         // The cross-reference to Item 7 doesn't end Item 1
+         assert!(sections[0].content_html.contains("demand grew"));
+         assert!(!sections[0].content_html.contains("Widget demand"));
+         // The last item of Part I ends at the Part II heading
+         assert!(sections[2].content_html.contains("We monitor threats."));
+         assert!(!sections[2].content_html.contains("PART II"));
+         assert!(sections[5].content_html.contains("Total assets"));
+         assert!(!sections[5].content_html.contains("Controls were effective"));
+         // Item 9 is omitted
+         assert!(sections[5].quality_flags.contains(&QualityFlag::UnexpectedSuccessor));
+         assert!(!sections[4].quality_flags.contains(&QualityFlag::UnexpectedSuccessor));
+
+         // Single items agree with the one-pass split
+         let risk = extractor.extract_section(html, ten_k_section("item 1a").unwrap(), &test_filing(), 5).unwrap();
+         assert_eq!(risk.content_html, sections[1].content_html);
+         assert_eq!(ten_k_section("Item 7").unwrap().successor.as_deref(), Some("Item 7A"));
+         assert_eq!(TEN_K_SECTIONS.last().unwrap().successor, None);
+         assert_eq!(TEN_K_ITEM_8.successor.as_deref(), Some("Item 9"));
+     }
+
+     #[test]
+     fn test_linked_toc() {
+         let html = r##"
+             <html><body>
+             <div><table>
+             <tr><td><a href="#i_8">Item 8.</a></td><td>Financial Statements and Supplementary Data</td><td><a href="#i_8">40</a></td></tr>
+             <tr><td><a href="#i_9">Item 9.</a></td><td>Changes in and Disagreements with Accountants</td><td><a href="#i_9">70</a></td></tr>
+             </table></div>
+             <div><span>Item 8. Financial Statements of acquired businesses are not required.</span></div>
+             <div id="i_8"></div>
+             <div><span style="font-weight:700">Item 8. Financial Statements and Supplementary Data</span></div>
+             <div>Consolidated balance sheets as of December 31.</div>
+             <div><a href="#top">Table of Contents</a></div>
+             <p><a name="i_9"></a><b>ITEM 9. CHANGES IN AND DISAGREEMENTS WITH ACCOUNTANTS</b></p>
+             <div>None.</div>
+             </body></html>
+         "##;
+         let document = Html::parse_document(html);
+         let extractor = DomExtractor::new();
+         let toc = extractor.harvest_toc_links(&document);
+         assert_eq!(toc.len(), 2, "page-number links merge into their row, back-links are skipped");
+         assert_eq!(clean_text(toc[1].target), "ITEM 9. CHANGES IN AND DISAGREEMENTS WITH ACCOUNTANTS");
+
+         // Text matching alone would start at the cross-reference above the real heading
+         let section = extractor.extract_section(html, &TEN_K_ITEM_8, &test_filing(), 10).unwrap();
+         assert!(section.content_html.contains("Consolidated balance sheets"));
+         assert!(!section.content_html.contains("acquired businesses"));
+         assert!(!section.content_html.contains("Supplementary Data"));
+         assert!(!section.content_html.contains("None."));
+     }
+
+     #[test]
+     fn test_linked_toc_sub_entries() {
+         let html = r##"
+             <html><body>
+             <div><table>
+             <tr><td><a href="#i_8">Item 8.</a></td><td>Financial Statements and Supplementary Data</td><td>40</td></tr>
+             <tr><td></td><td><a href="#auditor">Report of Independent Registered Public Accounting Firm</a></td><td>41</td></tr>
+             <tr><td></td><td><a href="#balance">Consolidated Balance Sheets</a></td><td>43</td></tr>
+             <tr><td><a href="#i_9">Item 9.</a></td><td>Changes in and Disagreements with Accountants</td><td>70</td></tr>
+             </table></div>
+             <h2 id="i_8">Item 8. Financial Statements and Supplementary Data</h2>
+             <p>Index to financial statements.</p>
+             <h3 id="auditor">Report of Independent Registered Public Accounting Firm</h3>
+             <p>We have audited the accompanying consolidated balance sheets.</p>
+             <h3 id="balance">Consolidated Balance Sheets</h3>
+             <table><tr><td>Total assets</td><td>1,234</td></tr></table>
+             <h2 id="i_9">Item 9. Changes in and Disagreements with Accountants</h2>
+             <p>None.</p>
+             </body></html>
+         "##;
+         let extractor = DomExtractor::new();
+         // The statements listed under Item 8 are part of it, not where it ends
+         for section in [
+             extractor.extract_section(html, &TEN_K_ITEM_8, &test_filing(), 10).unwrap(),
+             extractor.extract_all_sections(html, &TEN_K_SECTIONS, &test_filing(), 10).remove(0),
+         ] {
+             assert_eq!(section.section_name, "Item 8");
+             assert!(section.content_html.contains("We have audited"));
+             assert!(section.content_html.contains("Total assets"));
+             assert!(!section.content_html.contains("None."));
+         }
+     }
+
+     #[test]
+     fn test_ten_q_sections() {
+         let html = r#"
+             <html><body>
+             <h2>PART I. FINANCIAL INFORMATION</h2>
+             <h3>Item 1. Condensed Consolidated Financial Statements (Unaudited)</h3>
+             <table><tr><td>Net sales</td><td>300</td></tr></table>
+             <p>Notes to condensed consolidated financial statements.</p>
+             <h3>Item 2. Management’s Discussion and Analysis of Financial Condition and Results of Operations</h3>
+             <p>Quarterly sales rose on widget demand.</p>
+             <h3>Item 4. Controls and Procedures</h3>
+             <p>Disclosure controls were effective.</p>
+             <h2>PART II. OTHER INFORMATION</h2>
+             <h3>Item 1. Legal Proceedings</h3>
+             <p>None.</p>
+             <h3>Item 1A. Risk Factors</h3>
+             <p>No material changes from the annual report.</p>
+             <h3>Item 6. Exhibits</h3>
+             <p>31.1 Certification</p>
+             <p><b>SIGNATURES</b></p>
+             </body></html>
+         "#;
+         let extractor = DomExtractor::new();
+         let filing = FilingContext { form_type: "10-Q".to_string(), fiscal_quarter: Some(2), ..test_filing() };
+
+         let financials = extractor.extract_section(html, ten_q_section("Part I Item 1").unwrap(), &filing, 10).unwrap();
+         assert!(financials.content_html.contains("Net sales"));
+         assert!(!financials.content_html.contains("Quarterly sales"));
+         assert_eq!(financials.fiscal_quarter, Some(2));
+
+         // Item 3 is omitted, so MD&A ends at Item 4
+         let mdna = extractor.extract_section(html, ten_q_section("part i item 2").unwrap(), &filing, 10).unwrap();
+         assert!(mdna.content_html.contains("Quarterly sales"));
+         assert!(!mdna.content_html.contains("Disclosure controls"));
+
+         // Part II Item 1 must not be confused with Part I Item 1
+         let legal = extractor.extract_section(html, ten_q_section("Part II Item 1").unwrap(), &filing, 1).unwrap();
+         assert!(legal.content_html.contains("None."));
+         assert!(!legal.content_html.contains("No material changes"));
+
+         let risk = extractor.extract_section(html, ten_q_section("Part II Item 1A").unwrap(), &filing, 10).unwrap();
+         assert!(risk.content_html.contains("No material changes"));
+         assert!(!risk.content_html.contains("31.1"));
+
+         assert_eq!(TEN_Q_SECTIONS.len(), 11);
+     }
+
+     #[test]
+     fn test_twenty_f_sections() {
+         let html = r#"
+             <html><body>
+             <h2>PART I</h2>
+             <h3>Item 5. Operating and Financial Review and Prospects</h3>
+             <p>Revenue grew in all regions.</p>
+             <h3>Item 6. Directors, Senior Management and Employees</h3>
+             <p>Board composition.</p>
+             <h2>PART III</h2>
+             <h3>Item 17. Financial Statements</h3>
+             <p>Not applicable.</p>
+             <h3>Item 18. Financial Statements</h3>
+             <table><tr><td>Total assets</td><td>9,876</td></tr></table>
+             <h3>Item 19. Exhibits</h3>
+             <p>1.1 Articles of Association</p>
+             </body></html>
+         "#;
+         let extractor = DomExtractor::new();
+         let filing = FilingContext { form_type: "20-F".to_string(), ..test_filing() };
+
+         let review = extractor.extract_section(html, twenty_f_section("item 5").unwrap(), &filing, 10).unwrap();
+         assert!(review.content_html.contains("Revenue grew"));
+         assert!(!review.content_html.contains("Board composition"));
+
+         let financials = extractor.extract_section(html, twenty_f_section("Item 18").unwrap(), &filing, 10).unwrap();
+         assert!(financials.content_html.contains("Total assets"));
+         assert!(!financials.content_html.contains("Not applicable"));
+         assert!(!financials.content_html.contains("Articles of Association"));
+         assert_eq!(financials.section_title, "Financial Statements");
+     }
+}
@@ -0,0 +1,185 @@
+// src/extractors/text.rs
+//! Line-based section extraction for plain-text filings (late 1990s / early 2000s),
+//! which have no HTML elements for `DomExtractor` to work with.
+use crate::extractors::quality::{BoundaryMethod, Evidence};
+use crate::extractors::section::{ExtractedSection, FilingContext, SectionSpec};
+use crate::utils::content::escape_html;
+use crate::utils::error::ExtractError;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A line opening with a part or item number, e.g. "PART II" or "Item 7A."
+static ITEM_HEADING_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:PART\s+[IV]+\b|ITEM\s*\d+[A-Z]?\b)").expect("Failed to compile ITEM_HEADING_RE")
+});
+
+/// A page number at the end of a line, after dot leaders or a wide gap (ToC entries)
+static TOC_PAGE_NUMBER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\.{3,}|\s{3,}|\t)\s*(?:[A-Z]-)?\d{1,3}\s*$").expect("Failed to compile TOC_PAGE_NUMBER_RE")
+});
+
+/// Longest line still considered a heading
+const MAX_HEADING_LEN: usize = 160;
+
+/// Consecutive headings (blank lines aside) forming a list of headings, i.e. a ToC without page numbers
+const TOC_MIN_HEADING_RUN: usize = 3;
+
+pub struct TextExtractor;
+
+impl TextExtractor {
+    pub fn new() -> Self { Self {} }
+
+    /// Extracts the section described by `spec` from a plain-text document, matching the spec's
+    /// patterns against heading-like lines. The content is returned HTML-escaped in a `<pre>` block.
+    pub fn extract_section(
+        &self,
+        text: &str,
+        spec: &SectionSpec,
+        filing: &FilingContext,
+        min_section_size: usize,
+    ) -> Result<ExtractedSection, ExtractError> {
+        let (ticker, filing_year, section_name) = (&filing.ticker, filing.filing_year, &spec.name);
+        let min_section_size = spec.min_size.unwrap_or(min_section_size);
+        tracing::info!("Attempting text-based extraction for {}: {} ({}), min size {}", section_name, ticker, filing_year, min_section_size);
+
+        let lines: Vec<&str> = text.lines().collect();
+        let (start, end) = self.find_section_lines(&lines, spec)
+            .ok_or_else(|| ExtractError::SectionNotFound(format!("Could not find valid start/end lines for {} in text for {}-{}", section_name, ticker, filing_year)))?;
+
+        // The heading line itself is excluded, as in DomExtractor
+        let body = lines[start + 1..end].join("\n");
+        let content_html = format!("<pre>{}</pre>", escape_html(body.trim_matches('\n')));
+
+        if content_html.len() < min_section_size {
+            tracing::error!("Extracted {} text section is too small ({} bytes, required {}) for ticker {} ({}).", section_name, content_html.len(), min_section_size, ticker, filing_year);
+            return Err(ExtractError::SectionNotFound(format!("{} found but size {} bytes is less than minimum {} bytes", section_name, content_html.len(), min_section_size)));
+        }
+
+        tracing::info!("Successfully extracted {} via text for {} ({}): {} bytes", section_name, ticker, filing_year, content_html.len());
+        let evidence = Evidence { method: BoundaryMethod::PlainText, document_len: text.len(), ..Evidence::default() };
+        Ok(ExtractedSection::new(spec, filing, content_html).assessed(spec, &evidence))
+    }
+
+    /// Finds the start heading line (outside the ToC) and the first end heading line after it.
+    fn find_section_lines(&self, lines: &[&str], spec: &SectionSpec) -> Option<(usize, usize)> {
+        let start = (0..lines.len()).find(|&i| {
+            let line = lines[i].trim();
+            if !is_heading_like(line) || !spec.start_patterns.iter().any(|re| re.is_match(line)) {
+                return false;
+            }
+            if self.is_toc_line(lines, i) {
+                tracing::debug!("Skipping potential start line - likely in ToC: '{}'", line);
+                return false;
+            }
+            true
+        })?;
+        tracing::debug!("Selected start line {} for {}: '{}'", start + 1, spec.name, lines[start].trim());
+
+        let end = (start + 1..lines.len()).find(|&i| {
+            let line = lines[i].trim();
+            is_heading_like(line) && spec.end_patterns.iter().any(|re| re.is_match(line))
+        });
+        if end.is_none() {
+            tracing::warn!("No end marker found for '{}' after start line {}", spec.name, start + 1);
+        }
+        Some((start, end?))
+    }
+
+    /// A heading is in the table of contents if it ends with a page number, or if it is part of
+    /// a run of `TOC_MIN_HEADING_RUN` or more headings (a list of headings). A short section such
+    /// as "None." between two headings keeps them apart.
+    fn is_toc_line(&self, lines: &[&str], index: usize) -> bool {
+        if TOC_PAGE_NUMBER_RE.is_match(lines[index].trim_end()) {
+            return true;
+        }
+        let non_blank = |line: &&&str| !line.trim().is_empty();
+        let heading = |line: &&&str| ITEM_HEADING_RE.is_match(line);
+        let before = lines[..index].iter().rev().filter(non_blank).take_while(heading).count();
+        let after = lines[index + 1..].iter().filter(non_blank).take_while(heading).count();
+        before + 1 + after >= TOC_MIN_HEADING_RUN
+    }
+}
+
+/// Headings are short lines that open with a part/item number or are written in capitals.
+fn is_heading_like(line: &str) -> bool {
+    if line.is_empty() || line.len() > MAX_HEADING_LEN {
+        return false;
+    }
+    ITEM_HEADING_RE.is_match(line) || !line.chars().any(|c| c.is_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::section::{ten_k_section, TEN_K_ITEM_8};
+
+    const TEXT_10K: &str = "\
+                                TABLE OF CONTENTS
+
+PART II
+Item 7.   Management's Discussion and Analysis........................    14
+Item 8.   Financial Statements and Supplementary Data.................    20
+Item 9.   Changes in and Disagreements with Accountants...............    41
+
+                                    PART II
+
+ITEM 7.  MANAGEMENT'S DISCUSSION AND ANALYSIS
+
+Sales rose 12% in 1998.  The financial statements are in Item 8. Financial Statements
+and Supplementary Data below.
+
+ITEM 8.  FINANCIAL STATEMENTS AND SUPPLEMENTARY DATA
+
+                           CONSOLIDATED BALANCE SHEETS
+<TABLE>
+<S>                                        <C>          <C>
+Cash and equivalents                       $ 1,204      $   988
+Total assets                               $ 8,410      $ 7,652
+</TABLE>
+<PAGE>
+
+ITEM 9.  CHANGES IN AND DISAGREEMENTS WITH ACCOUNTANTS ON ACCOUNTING AND
+         FINANCIAL DISCLOSURE
+
+None.
+";
+
+    #[test]
+    fn test_plain_text_item_8() {
+        let filing = FilingContext { ticker: "OLDC".to_string(), filing_year: 1998, ..FilingContext::default() };
+        let section = TextExtractor::new().extract_section(TEXT_10K, &TEN_K_ITEM_8, &filing, 100).unwrap();
+        assert!(section.content_html.starts_with("<pre>"));
+        assert!(section.content_html.contains("CONSOLIDATED BALANCE SHEETS"));
+        assert!(section.content_html.contains("&lt;TABLE&gt;"));
+        assert!(!section.content_html.contains("ITEM 9."));
+        assert!(!section.content_html.contains("Sales rose"), "ToC entry and MD&A cross-reference are not the start");
+        assert_eq!(section.section_name, "Item 8");
+        assert_eq!(section.filing_year, 1998);
+    }
+    #[test]
+    fn test_one_line_section_is_not_toc() {
+        let text = "\
+TABLE OF CONTENTS
+Item 1A. Risk Factors
+Item 1B. Unresolved Staff Comments
+Item 1C. Cybersecurity
+
+PART I
+
+Item 1A. Risk Factors
+
+Our business is subject to risks.
+
+Item 1B. Unresolved Staff Comments
+
+None.
+
+Item 1C. Cybersecurity
+
+We maintain a cybersecurity program.
+";
+        let spec = ten_k_section("Item 1B").unwrap();
+        let section = TextExtractor::new().extract_section(text, spec, &FilingContext::default(), 10).unwrap();
+        assert_eq!(section.content_html, "<pre>None.</pre>");
+    }
+}
@@ -2,31 +2,75 @@
 mod utils;
 mod edgar;
 mod extractors;
+mod pipeline;
 mod storage;
 
 use clap::Parser; // <<< Ensure this use statement is present
 use utils::AppError;
-use edgar::client;
-use extractors::section::DomExtractor;
+use edgar::cache::{CacheTtls, HttpCache};
+use edgar::client::EdgarClient;
+use edgar::config::EdgarConfig;
+use edgar::forms::{check_supported, AmendmentPolicy, FormFilter, ReportType};
+use edgar::offline::FixtureStore;
+use extractors::definitions::{merge_catalogue, CustomSections};
+use extractors::section::{ten_k_section, ten_q_section, twenty_f_section, SectionSpec, TEN_K_ITEM_8, TEN_K_SECTIONS, TEN_Q_SECTIONS, TWENTY_F_SECTIONS};
+use pipeline::batch::{dedup_identifiers, find_universe_filings, read_universe_file, SicFilter};
+use pipeline::report::{CompanyReport, CompanyStatus, FilingReport, RunReport};
+use pipeline::{Pipeline, PipelineOptions};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
 use storage::StorageManager;
 
 /// Command Line Interface for SEC Item 8 Parser
 #[derive(Parser, Debug)] // <<< derive(Parser) needs 'use clap::Parser;'
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Ticker symbol of the company
-    #[arg(short, long)]
-    ticker: String,
+    /// Companies to process: tickers (BRK.B and BRK-B both work), CIKs or company names.
+    /// Repeat the option or separate with commas (optional with --accession-number or --universe)
+    #[arg(short, long, value_delimiter = ',', required_unless_present_any = ["accession_number", "universe"])]
+    ticker: Vec<String>,
+
+    /// File listing companies to process, one ticker, CIK or company name per line (# starts a comment)
+    #[arg(long, conflicts_with = "accession_number")]
+    universe: Option<std::path::PathBuf>,
+
+    /// Only process companies with these SIC codes, e.g. "3571" or "6000-6799,7372"
+    #[arg(long, conflicts_with = "accession_number")]
+    sic: Option<SicFilter>,
+
+    /// Report type to process: annual (10-K family), quarterly (10-Q family) or foreign (20-F)
+    #[arg(long, default_value_t = ReportType::Annual)]
+    report_type: ReportType,
 
-    /// Start year for the 10-K filings (optional)
+    /// Section to extract, e.g. "Item 1A" for a 10-K, "Part I Item 2" for a 10-Q or "Item 5" for a 20-F,
+    /// or "all" for every item (default: the financial statements - Item 8, Part I Item 1 or Item 18)
+    #[arg(long)]
+    section: Option<String>,
+
+    /// Section definitions file (TOML, or JSON with a .json extension) overriding or extending
+    /// the built-in sections: [[annual]], [[quarterly]] and [[foreign]] lists of name, title,
+    /// start_patterns, end_patterns and optional selector, min_size and successor
+    #[arg(long)]
+    sections: Option<std::path::PathBuf>,
+
+    /// Form types to search, comma-separated (default: the report type's family, e.g. 10-K, 10-K405, 10-KT, 10-KSB)
+    #[arg(long, value_delimiter = ',')]
+    forms: Option<Vec<String>>,
+
+    /// How amended filings (e.g. 10-K/A) are handled: keep-all, prefer-latest or originals-only
+    #[arg(long, default_value_t = AmendmentPolicy::OriginalsOnly)]
+    amendments: AmendmentPolicy,
+
+    /// First fiscal year to process (optional)
     #[arg(long)]
     start_year: Option<u32>,
 
-    /// End year for the 10-K filings (optional)
+    /// Last fiscal year to process (optional)
     #[arg(long)]
     end_year: Option<u32>,
 
-    /// Specific SEC accession number (optional, overrides ticker/year)
+    /// Specific SEC accession number, with or without dashes (optional, overrides year/form filters).
+    /// Add --ticker if the filing was submitted by a filing agent
     #[arg(short, long)]
     accession_number: Option<String>,
 
@@ -34,13 +78,109 @@ struct Args {
     #[arg(short, long, default_value = "./output")]
     output_dir: String,
 
-    /// Debug mode - save raw HTML files for debugging failures
+    /// Debug mode - save raw HTML files and boundary scores for debugging failures
     #[arg(short, long)]
     debug: bool,
 
     /// Set minimum section size in bytes (default: 1000)
     #[arg(long, default_value = "1000")]
     min_section_size: usize,
+
+    /// Number of filings processed concurrently (EDGAR requests still respect the rate limit)
+    #[arg(short, long, default_value = "4")]
+    jobs: NonZeroUsize,
+
+    /// Path to a TOML config file (default: ./sec_extractor.toml if present)
+    #[arg(long)]
+    config: Option<std::path::PathBuf>,
+
+    /// Name/organisation sent to SEC in the User-Agent header (overrides config/env)
+    #[arg(long)]
+    user_agent: Option<String>,
+
+    /// Contact email sent to SEC in the User-Agent header (overrides config/env)
+    #[arg(long)]
+    contact_email: Option<String>,
+
+    /// Maximum EDGAR requests per second, 1-10 (overrides config/env)
+    #[arg(long)]
+    requests_per_second: Option<u32>,
+
+    /// HTTP request timeout in seconds (overrides config/env)
+    #[arg(long)]
+    timeout_secs: Option<u64>,
+
+    /// Disable the on-disk EDGAR response cache (<output_dir>/.cache/edgar)
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Never touch the network: serve EDGAR requests from the cache or --fixtures-dir only
+    #[arg(long)]
+    offline: bool,
+
+    /// Directory of recorded EDGAR responses (<dir>/<host>/<path>) used by --offline
+    #[arg(long, requires = "offline")]
+    fixtures_dir: Option<std::path::PathBuf>,
+
+    /// Base URL for EDGAR archives, e.g. a local mirror (default: https://www.sec.gov/Archives)
+    #[arg(long)]
+    archives_url: Option<String>,
+
+    /// Base URL for the EDGAR JSON data API (default: https://data.sec.gov)
+    #[arg(long)]
+    data_url: Option<String>,
+
+    /// Base URL for EDGAR static files such as company_tickers_exchange.json (default: https://www.sec.gov/files)
+    #[arg(long)]
+    files_url: Option<String>,
+
+    /// Retries for transient EDGAR failures, 0 disables retrying (overrides config/env)
+    #[arg(long)]
+    max_retries: Option<u32>,
+}
+
+/// Builds the EDGAR configuration: defaults < config file < environment < CLI flags.
+fn load_edgar_config(args: &Args) -> Result<EdgarConfig, AppError> {
+    let mut config = EdgarConfig::load(args.config.as_deref())?;
+    if let Some(user_agent) = &args.user_agent { config.user_agent = user_agent.clone(); }
+    if let Some(contact_email) = &args.contact_email { config.contact_email = contact_email.clone(); }
+    if let Some(rps) = args.requests_per_second { config.requests_per_second = rps; }
+    if let Some(timeout) = args.timeout_secs { config.timeout_secs = timeout; }
+    if let Some(retries) = args.max_retries { config.max_retries = retries; }
+    if let Some(url) = &args.archives_url { config.archives_base_url = url.clone(); }
+    if let Some(url) = &args.data_url { config.data_base_url = url.clone(); }
+    if let Some(url) = &args.files_url { config.files_base_url = url.clone(); }
+    if args.offline {
+        // No request reaches SEC, so the identity doesn't matter
+        config.validate_settings()?;
+    } else {
+        config.validate()?;
+    }
+    tracing::debug!("Using EDGAR User-Agent: {}", config.user_agent_header());
+    Ok(config)
+}
+
+/// The sections to extract for a report type: `--section` if given ("all" for the whole
+/// catalogue), else the financial statements. Sections from `--sections` take precedence.
+fn section_specs(args: &Args, report_type: ReportType, custom: &CustomSections) -> Result<Vec<SectionSpec>, AppError> {
+    type Lookup = fn(&str) -> Option<&'static SectionSpec>;
+    let (catalogue, custom, lookup, default): (&[SectionSpec], &[SectionSpec], Lookup, &str) = match report_type {
+        ReportType::Annual => (&TEN_K_SECTIONS, &custom.annual, ten_k_section, &TEN_K_ITEM_8.name),
+        ReportType::Quarterly => (&TEN_Q_SECTIONS, &custom.quarterly, ten_q_section, "Part I Item 1"),
+        ReportType::ForeignAnnual => (&TWENTY_F_SECTIONS, &custom.foreign, twenty_f_section, "Item 18"),
+    };
+    let name = args.section.as_deref().map(str::trim).unwrap_or(default);
+    if name.eq_ignore_ascii_case("all") {
+        return Ok(merge_catalogue(catalogue, custom));
+    }
+    let spec = if custom.iter().any(|spec| spec.name.eq_ignore_ascii_case(name)) {
+        // Merged, so a redefined built-in section keeps its name, title and successor
+        merge_catalogue(catalogue, custom).into_iter().find(|spec| spec.name.eq_ignore_ascii_case(name))
+    } else {
+        lookup(name).cloned()
+    };
+    spec.map(|spec| vec![spec])
+        .ok_or_else(|| AppError::Config(format!("Unknown section '{}' for {} reports", name, report_type)))
 }
 
 #[tokio::main]
@@ -53,133 +193,131 @@ async fn main() -> Result<(), AppError> {
     tracing::info!("Starting processing for args: {:?}", args);
     tracing::debug!("Minimum section size set to: {}", args.min_section_size);
 
-    // 3. Initialize storage
+    // 3. Resolve EDGAR configuration (fails fast on placeholder identity)
+    let edgar_config = load_edgar_config(&args)?;
+
+    // 3a. Load and validate custom section definitions before anything is downloaded
+    let custom_sections = match &args.sections {
+        Some(path) => CustomSections::load(path)?,
+        None => CustomSections::default(),
+    };
+
+    // 3b. Initialize storage
     let storage = StorageManager::new(&args.output_dir)?;
 
-    // 4. Initialize section extractor
-    let section_extractor = DomExtractor::new();
+    // 3c. Create the shared EDGAR client, backed by the response cache unless disabled
+    let cache_ttls = CacheTtls::from_config(&edgar_config);
+    let mut edgar = EdgarClient::new(edgar_config)?;
+    if !args.no_cache {
+        edgar = edgar.with_cache(HttpCache::new(storage.cache_dir(), cache_ttls)?);
+    }
+    if args.offline {
+        if args.no_cache && args.fixtures_dir.is_none() {
+            return Err(AppError::Config("--offline needs the response cache or --fixtures-dir to serve requests".to_string()));
+        }
+        tracing::info!("Offline mode: serving EDGAR requests from cache{}",
+                       args.fixtures_dir.as_ref().map(|d| format!(" and fixtures in {}", d.display())).unwrap_or_default());
+        edgar = edgar.offline(args.fixtures_dir.as_ref().map(FixtureStore::new));
+    }
+
+    let started_at = chrono::Utc::now().to_rfc3339();
 
-    // 5. If accession number is provided, process just that filing (Placeholder)
-    if let Some(accession) = &args.accession_number {
+    // 5. If accession number is provided, process just that filing; its form decides the report type
+    let (filings, report_type, mut companies, no_filings) = if let Some(accession) = &args.accession_number {
         tracing::info!("Processing specific filing: {}", accession);
-        // TODO: Implement specific filing processing logic here
-        // You would need to fetch company info differently or assume it's known
-        // Then download the specific filing using a constructed URL
-        return Err(AppError::Config("Processing by accession number not yet implemented".to_string()));
-    }
+        let filing = edgar.find_filing_by_accession(accession, args.ticker.first().map(String::as_str)).await?;
+        check_supported(&filing.form_type).map_err(AppError::Config)?;
+        let report_type = ReportType::for_form(&filing.form_type).unwrap_or(args.report_type);
+        let mut company = CompanyReport::new(accession, CompanyStatus::Processed);
+        company.cik = Some(filing.cik.clone());
+        company.ticker = Some(filing.ticker.clone());
+        company.company_name = Some(filing.company_name.clone());
+        company.exchange = filing.exchange.clone();
+        (vec![filing], report_type, vec![company], None)
+    } else {
+        // 6. Find the filings of every company in the universe (--ticker and --universe)
+        let mut identifiers = args.ticker.clone();
+        if let Some(path) = &args.universe {
+            let listed = read_universe_file(path)
+                .map_err(|e| AppError::Config(format!("Cannot read universe file {}: {}", path.display(), e)))?;
+            identifiers.extend(listed);
+        }
+        let identifiers = dedup_identifiers(identifiers);
+        if identifiers.is_empty() {
+            return Err(AppError::Config("No companies to process".to_string()));
+        }
+        tracing::info!("Finding {} report filings for {} companies", args.report_type, identifiers.len());
+        let form_filter = match &args.forms {
+            Some(forms) => {
+                forms.iter().try_for_each(|form| check_supported(form)).map_err(AppError::Config)?;
+                FormFilter::from_forms(forms, args.amendments)
+            }
+            None => FormFilter::for_report_type(args.report_type, args.amendments),
+        };
+        tracing::debug!("Searching forms {:?} (amendments: {})", form_filter.forms(), form_filter.amendments);
+        let (filings, companies) = find_universe_filings(
+            &edgar,
+            &identifiers,
+            args.start_year,
+            args.end_year,
+            &form_filter,
+            args.sic.as_ref(),
+        ).await;
 
-    // --- Added Missing Logic: Fetching Filings and Loop ---
-    // 6. Find 10-K filings for the ticker
-    tracing::info!("Finding 10-K filings for ticker: {}", args.ticker);
-    let filings = client::find_10k_filings(
-        &args.ticker,
-        args.start_year,
-        args.end_year
-    ).await?; // <<< Define 'filings'
+        tracing::info!("Found {} filings", filings.len());
 
-    tracing::info!("Found {} 10-K filings", filings.len());
+        // Without filings the run report still records why each company yielded none
+        let no_filings = filings.is_empty().then(|| format!("No {} filings found for {} in the specified date range",
+                                                            form_filter.forms().join("/"), identifiers.join(", ")));
+        (filings, args.report_type, companies, no_filings)
+    };
 
-    if filings.is_empty() {
-        return Err(AppError::Config(format!("No 10-K filings found for ticker {} in the specified date range", args.ticker)));
-    }
+    // 6b. Pick the sections to extract
+    let specs = section_specs(&args, report_type, &custom_sections)?;
+    let section = match specs.as_slice() {
+        [spec] => spec.name.clone(),
+        _ => "all".to_string(),
+    };
 
-    // 7. Process each filing - Initialize counters outside the loop
-    let mut success_count = 0; // <<< Define counters
-    let mut failure_count = 0;
-
-    for filing in filings { // <<< Start the loop, defines 'filing'
-        tracing::info!("Processing filing for year: {:?} ({})", filing.year, filing.accession_number);
-
-        // Download the filing document
-        let url = filing.primary_doc_url(); // <<< Define 'url'
-        tracing::info!("Downloading from URL: {}", url);
-
-        match client::download_filing_doc(&url).await {
-            Ok(content) => {
-                tracing::info!("Successfully downloaded document ({} bytes)", content.len());
-
-                // --- Debugging Section ---
-                // Create debug directory path regardless of args.debug, used for failure logs too
-                 let debug_dir = format!("{}/{}/{}/debug",
-                                        args.output_dir,
-                                        filing.ticker.to_uppercase(),
-                                        filing.year.unwrap_or(0)); // Use 0 if year is None for path
-
-
-                if args.debug {
-                    // Ensure debug directory exists
-                     if let Err(e) = std::fs::create_dir_all(&debug_dir) {
-                         tracing::error!("Failed to create debug directory {}: {}", debug_dir, e);
-                         // Decide if this is fatal or just skip debug saving
-                     } else {
-                         // Save the raw filing for debugging
-                         let raw_filing_path = format!("{}/raw_filing.html", debug_dir);
-                         if let Err(e) = std::fs::write(&raw_filing_path, &content) {
-                             tracing::error!("Failed to save raw filing to {}: {}", raw_filing_path, e);
-                         } else {
-                             tracing::info!("Saved raw filing to: {}", raw_filing_path);
-                         }
-                     }
-
-                    // TODO: Update or remove html_debug::create_debug_html
-                    tracing::warn!("Skipping annotated debug HTML generation - needs rework for DOM approach.");
-                }
-
-                // --- Extraction Call ---
-                if let Some(year) = filing.year { // <<< Check optional year
-                     match section_extractor.extract_item_8(
-                        &content,
-                        year,
-                        &filing.company_name,
-                        &filing.ticker,
-                        args.min_section_size
-                    ) {
-                        Ok(section) => {
-                            tracing::info!("Successfully extracted Item 8 section ({} bytes)", section.content_html.len());
-                            success_count += 1; // <<< Increment counter
-
-                            // Save the section content
-                            match storage.save_section(&section) {
-                                Ok(path) => tracing::info!("Saved section content to: {}", path.display()),
-                                Err(e) => tracing::error!("Failed to save section content: {}", e),
-                            }
-
-                            // Save the section metadata
-                             match storage.save_section_metadata(&section) {
-                                 Ok(path) => tracing::info!("Saved section metadata to: {}", path.display()),
-                                 Err(e) => tracing::error!("Failed to save section metadata: {}", e),
-                             }
-                        },
-                        Err(e) => {
-                            tracing::error!("Failed to extract Item 8 section for {}-{}: {}", filing.ticker, year, e);
-                            failure_count += 1; // <<< Increment counter
-                            // Optional: Save failure info even if not in debug mode, but save to debug dir
-                            // Ensure debug directory exists before writing failure info
-                             if let Err(e) = std::fs::create_dir_all(&debug_dir) {
-                                 tracing::error!("Failed to create debug directory {} for failure log: {}", debug_dir, e);
-                             } else {
-                                 let failure_info_path = format!("{}/extraction_failure.txt", debug_dir);
-                                 let failure_info = format!("Failed to extract Item 8 for {} {}: {}\nURL: {}\n",
-                                                            filing.ticker, year, e, url); // <<< Use defined 'url'
-                                 if let Err(write_err) = std::fs::write(&failure_info_path, failure_info) {
-                                     tracing::error!("Failed to save failure info: {}", write_err);
-                                 } else {
-                                     tracing::debug!("Saved extraction failure info to {}", failure_info_path);
-                                 }
-                             }
-                        }
-                    }
-                } else {
-                    tracing::warn!("Filing year not available for {}, skipping extraction", filing.accession_number);
-                    failure_count += 1; // <<< Increment counter
-                }
-            },
-            Err(e) => {
-                tracing::error!("Failed to download filing document {}: {}", url, e); // <<< Use defined 'url'
-                failure_count += 1; // <<< Increment counter
-            }
+    // 7. Process the filings concurrently
+    let options = PipelineOptions {
+        output_dir: args.output_dir.clone(),
+        debug: args.debug,
+        min_section_size: args.min_section_size,
+        jobs: args.jobs,
+    };
+    let pipeline = Arc::new(Pipeline::new(edgar, storage, specs, options));
+    let results = Arc::clone(&pipeline).run(filings).await;
+
+    // 8. Aggregate the run report, per company in universe order and per filing in filing order
+    for result in &results {
+        if let Some(company) = companies.iter_mut().find(|c| c.status == CompanyStatus::Processed && c.cik.as_deref() == Some(&result.cik)) {
+            company.filings.push(FilingReport::from(result));
         }
-    } // <<< End of the loop
+    }
+    for company in &companies {
+        tracing::info!("{}", company.summary());
+    }
+    let report = RunReport {
+        started_at,
+        finished_at: chrono::Utc::now().to_rfc3339(),
+        report_type: report_type.to_string(),
+        section: section.clone(),
+        start_year: args.start_year,
+        end_year: args.end_year,
+        sic_filter: args.sic.as_ref().map(ToString::to_string),
+        totals: RunReport::totals_of(&companies),
+        companies,
+    };
+    match pipeline.storage().save_run_report(&report) {
+        Ok(path) => tracing::info!("Saved run report to: {}", path.display()),
+        Err(e) => tracing::error!("Failed to save run report: {}", e),
+    }
+    if let Some(message) = no_filings {
+        return Err(AppError::Config(message));
+    }
+    let success_count = report.totals.succeeded;
+    let failure_count = report.totals.failed;
 
     // --- Final Summary ---
     tracing::info!("Processing finished. Success: {}, Failures: {}", success_count, failure_count);
@@ -190,7 +328,7 @@ async fn main() -> Result<(), AppError> {
         // Consider not erroring out if *some* filings were processed, even if others failed.
         // For now, error out if ALL attempts failed.
          tracing::error!("All extraction attempts failed.");
-         return Err(AppError::Processing(format!("Failed to extract any Item 8 sections from {} filings attempted", failure_count)));
+         return Err(AppError::Processing(format!("Failed to extract any {} sections from {} filings attempted", section, failure_count)));
 
     }
 
@@ -0,0 +1,214 @@
+// src/pipeline/batch.rs
+//! Company universe for batch runs: identifiers from the command line and universe files,
+//! and the SIC code filter applied to them.
+use crate::edgar::client::EdgarClient;
+use crate::edgar::forms::FormFilter;
+use crate::edgar::models::FilingInfo;
+use crate::pipeline::report::{CompanyReport, CompanyStatus};
+use crate::utils::error::EdgarError;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Reads a universe file: one ticker, CIK or company name per line.
+/// Blank lines and `#` comments are ignored.
+pub fn read_universe_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+    Ok(parse_universe(&fs::read_to_string(path)?))
+}
+
+fn parse_universe(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Identifiers in the order given, without repeats (compared case-insensitively).
+pub fn dedup_identifiers(identifiers: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for identifier in identifiers {
+        let identifier = identifier.trim().to_string();
+        if !identifier.is_empty() && !unique.iter().any(|u| u.eq_ignore_ascii_case(&identifier)) {
+            unique.push(identifier);
+        }
+    }
+    unique
+}
+
+/// Resolves each identifier and lists its filings. Companies that can't be resolved, fail the
+/// SIC filter, or have no matching filings are recorded in their report instead of aborting the run.
+/// Returns the filings of all companies, in universe order, and one report per identifier.
+pub async fn find_universe_filings(
+    edgar: &EdgarClient,
+    identifiers: &[String],
+    start_year: Option<u32>,
+    end_year: Option<u32>,
+    forms: &FormFilter,
+    sic_filter: Option<&SicFilter>,
+) -> (Vec<FilingInfo>, Vec<CompanyReport>) {
+    let mut filings = Vec::new();
+    let mut reports: Vec<CompanyReport> = Vec::new();
+
+    for identifier in identifiers {
+        let company = match edgar.resolve_company(identifier).await {
+            Ok(company) => company,
+            Err(e @ (EdgarError::CompanyNotFound(_) | EdgarError::AmbiguousCompany { .. })) => {
+                tracing::warn!("Skipping '{}': {}", identifier, e);
+                reports.push(CompanyReport::new(identifier, CompanyStatus::NotFound).with_message(e.to_string()));
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("Could not look up '{}': {}", identifier, e);
+                reports.push(CompanyReport::new(identifier, CompanyStatus::Error).with_message(e.to_string()));
+                continue;
+            }
+        };
+
+        let mut report = CompanyReport::new(identifier, CompanyStatus::Processed);
+        report.cik = Some(company.cik.clone());
+        report.ticker = company.ticker.clone();
+        report.exchange = company.exchange.clone();
+        if let Some(first) = reports.iter().find(|r| r.status != CompanyStatus::Duplicate && r.cik == report.cik) {
+            tracing::info!("Skipping '{}': same company as '{}'", identifier, first.input);
+            let message = format!("same company as '{}'", first.input);
+            report.status = CompanyStatus::Duplicate;
+            reports.push(report.with_message(message));
+            continue;
+        }
+
+        let submissions = match edgar.get_company_submissions(&company.cik).await {
+            Ok(submissions) => submissions,
+            Err(e) => {
+                tracing::error!("Could not load submissions of '{}' (CIK {}): {}", identifier, company.cik, e);
+                report.status = CompanyStatus::Error;
+                reports.push(report.with_message(e.to_string()));
+                continue;
+            }
+        };
+        report.company_name = Some(submissions.name.clone());
+        report.sic = Some(submissions.sic.clone()).filter(|sic| !sic.is_empty());
+        if let Some(filter) = sic_filter.filter(|filter| !filter.matches(&submissions.sic)) {
+            tracing::info!("Skipping {} (SIC {}): not in {}", submissions.name, submissions.sic, filter);
+            report.status = CompanyStatus::ExcludedBySic;
+            let message = format!("SIC {} ({}) not in {}", submissions.sic, submissions.sicDescription, filter);
+            reports.push(report.with_message(message));
+            continue;
+        }
+
+        match edgar.filings_from_submissions(&company, submissions, start_year, end_year, forms).await {
+            Ok(found) if found.is_empty() => {
+                report.status = CompanyStatus::NoFilings;
+                let message = format!("no {} filings in the year range", forms.forms().join("/"));
+                reports.push(report.with_message(message));
+            }
+            Ok(found) => {
+                tracing::info!("Found {} filings for '{}'", found.len(), identifier);
+                report.ticker = found.first().map(|f| f.ticker.clone());
+                filings.extend(found);
+                reports.push(report);
+            }
+            Err(e) => {
+                tracing::error!("Could not list filings of '{}': {}", identifier, e);
+                report.status = CompanyStatus::Error;
+                reports.push(report.with_message(e.to_string()));
+            }
+        }
+    }
+    (filings, reports)
+}
+
+/// SIC codes a company must have to be processed: single codes and inclusive ranges,
+/// e.g. "3571,6000-6799".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SicFilter {
+    ranges: Vec<(u16, u16)>,
+}
+
+impl SicFilter {
+    /// Whether a company's SIC code (as in the submissions JSON; may be empty) passes the filter.
+    pub fn matches(&self, sic: &str) -> bool {
+        sic.trim().parse::<u16>()
+            .is_ok_and(|code| self.ranges.iter().any(|&(low, high)| (low..=high).contains(&code)))
+    }
+}
+
+impl FromStr for SicFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = |c: &str| c.trim().parse::<u16>().map_err(|_| format!("invalid SIC code '{}'", c.trim()));
+        let mut ranges = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let range = match part.split_once('-') {
+                Some((low, high)) => (code(low)?, code(high)?),
+                None => (code(part)?, code(part)?),
+            };
+            if range.0 > range.1 {
+                return Err(format!("invalid SIC range '{}'", part));
+            }
+            ranges.push(range);
+        }
+        if ranges.is_empty() {
+            return Err("no SIC codes given".to_string());
+        }
+        Ok(Self { ranges })
+    }
+}
+
+impl fmt::Display for SicFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.ranges.iter()
+            .map(|&(low, high)| if low == high { low.to_string() } else { format!("{}-{}", low, high) })
+            .collect();
+        f.write_str(&parts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_universe_file_parsing() {
+        let universe = parse_universe("# Portfolio\nAAPL\n\n  BRK.B  # class B\n320193\nBerkshire Hathaway\n");
+        assert_eq!(universe, ["AAPL", "BRK.B", "320193", "Berkshire Hathaway"]);
+        let unique = dedup_identifiers(["aapl".to_string(), "MSFT".to_string(), "AAPL".to_string()]);
+        assert_eq!(unique, ["aapl", "MSFT"]);
+    }
+
+    #[tokio::test]
+    async fn test_universe_filings() {
+        use crate::edgar::forms::{AmendmentPolicy, ReportType};
+        use crate::edgar::offline::offline_client;
+
+        let edgar = offline_client();
+        let forms = FormFilter::for_report_type(ReportType::Annual, AmendmentPolicy::OriginalsOnly);
+        let universe: Vec<String> = ["tstc", "1234567", "Nonexistent Mining"].map(String::from).to_vec();
+
+        let (filings, reports) = find_universe_filings(&edgar, &universe, Some(2023), Some(2023), &forms, None).await;
+        assert_eq!(filings.len(), 1);
+        let statuses: Vec<CompanyStatus> = reports.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [CompanyStatus::Processed, CompanyStatus::Duplicate, CompanyStatus::NotFound]);
+        assert_eq!(reports[0].sic.as_deref(), Some("3571"));
+
+        let banks: SicFilter = "6000-6799".parse().unwrap();
+        let (filings, reports) = find_universe_filings(&edgar, &universe[..1], None, None, &forms, Some(&banks)).await;
+        assert!(filings.is_empty());
+        assert_eq!(reports[0].status, CompanyStatus::ExcludedBySic);
+    }
+
+    #[test]
+    fn test_sic_filter() {
+        let filter: SicFilter = "3571, 6000-6799".parse().unwrap();
+        assert!(filter.matches("3571"));
+        assert!(filter.matches("6022"));
+        assert!(!filter.matches("3572"));
+        assert!(!filter.matches(""));
+        assert_eq!(filter.to_string(), "3571,6000-6799");
+        assert!("6799-6000".parse::<SicFilter>().is_err());
+        assert!("banks".parse::<SicFilter>().is_err());
+    }
+}
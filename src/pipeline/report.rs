@@ -0,0 +1,194 @@
+// src/pipeline/report.rs
+//! Aggregated run report: per company, which filings (fiscal years) were extracted and which failed.
+use crate::pipeline::{FilingOutcome, FilingResult};
+use serde::Serialize;
+
+/// How far a company of the universe got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompanyStatus {
+    /// Filings found and processed (individual filings may still have failed)
+    Processed,
+    /// The identifier matched no company
+    NotFound,
+    /// Skipped by the `--sic` filter
+    ExcludedBySic,
+    /// No filings of the requested forms in the year range
+    NoFilings,
+    /// Same CIK as an earlier identifier in the universe
+    Duplicate,
+    /// EDGAR lookup failed (network, parsing, ...)
+    Error,
+}
+
+/// One filing of a company.
+#[derive(Debug, Clone, Serialize)]
+pub struct FilingReport {
+    pub fiscal_year: Option<u32>,
+    pub fiscal_quarter: Option<u8>,
+    pub form_type: String,
+    pub accession_number: String,
+    pub success: bool,
+    /// Saved section files (several with `--section all`)
+    pub output_paths: Vec<String>,
+    /// Size of the extracted sections in bytes
+    pub content_length: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl From<&FilingResult> for FilingReport {
+    fn from(result: &FilingResult) -> Self {
+        let (output_paths, content_length, error) = match &result.outcome {
+            FilingOutcome::Extracted { paths, bytes } => {
+                (paths.iter().map(|path| path.display().to_string()).collect(), Some(*bytes), None)
+            }
+            FilingOutcome::Failed(reason) => (Vec::new(), None, Some(reason.clone())),
+        };
+        Self {
+            fiscal_year: result.year,
+            fiscal_quarter: result.fiscal_quarter,
+            form_type: result.form_type.clone(),
+            accession_number: result.accession_number.clone(),
+            success: result.is_success(),
+            output_paths,
+            content_length,
+            error,
+        }
+    }
+}
+
+/// One entry of the universe, as given on the command line or in the universe file.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompanyReport {
+    pub input: String,
+    pub status: CompanyStatus,
+    pub cik: Option<String>,
+    pub ticker: Option<String>,
+    pub company_name: Option<String>,
+    pub sic: Option<String>,
+    pub exchange: Option<String>,
+    /// Why the company wasn't processed, for statuses other than `processed`
+    pub message: Option<String>,
+    pub filings: Vec<FilingReport>,
+}
+
+impl CompanyReport {
+    pub fn new(input: &str, status: CompanyStatus) -> Self {
+        Self {
+            input: input.to_string(),
+            status,
+            cik: None,
+            ticker: None,
+            company_name: None,
+            sic: None,
+            exchange: None,
+            message: None,
+            filings: Vec::new(),
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.filings.iter().filter(|f| f.success).count()
+    }
+
+    /// One-line summary, e.g. "AAPL (CIK 0000320193): 3/4 extracted, failed: 2021"
+    pub fn summary(&self) -> String {
+        let name = match (&self.ticker, &self.cik) {
+            (Some(ticker), Some(cik)) => format!("{} (CIK {})", ticker, cik),
+            (None, Some(cik)) => format!("{} (CIK {})", self.input, cik),
+            _ => self.input.clone(),
+        };
+        if self.status != CompanyStatus::Processed {
+            let status = serde_json::to_value(self.status).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+            return format!("{}: {}{}", name, status.replace('_', " "),
+                           self.message.as_deref().map(|m| format!(" - {}", m)).unwrap_or_default());
+        }
+        let failed: Vec<String> = self.filings.iter()
+            .filter(|f| !f.success)
+            .map(|f| format!("{}{}", f.fiscal_year.map(|y| y.to_string()).unwrap_or_else(|| "?".to_string()),
+                             f.fiscal_quarter.map(|q| format!("Q{}", q)).unwrap_or_default()))
+            .collect();
+        let mut line = format!("{}: {}/{} extracted", name, self.succeeded(), self.filings.len());
+        if !failed.is_empty() {
+            line.push_str(&format!(", failed: {}", failed.join(", ")));
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunTotals {
+    pub companies: usize,
+    pub companies_processed: usize,
+    pub filings: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Report of a whole run, saved as `run_report.json` in the output directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunReport {
+    pub started_at: String,
+    pub finished_at: String,
+    pub report_type: String,
+    pub section: String,
+    pub start_year: Option<u32>,
+    pub end_year: Option<u32>,
+    pub sic_filter: Option<String>,
+    pub totals: RunTotals,
+    pub companies: Vec<CompanyReport>,
+}
+
+impl RunReport {
+    pub fn totals_of(companies: &[CompanyReport]) -> RunTotals {
+        let filings = companies.iter().map(|c| c.filings.len()).sum();
+        let succeeded = companies.iter().map(CompanyReport::succeeded).sum();
+        RunTotals {
+            companies: companies.len(),
+            companies_processed: companies.iter().filter(|c| c.status == CompanyStatus::Processed).count(),
+            filings,
+            succeeded,
+            failed: filings - succeeded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn result(year: u32, outcome: FilingOutcome) -> FilingResult {
+        FilingResult {
+            cik: "0001234567".to_string(),
+            accession_number: format!("0001234567-{}-000010", year % 100),
+            form_type: "10-K".to_string(),
+            year: Some(year),
+            fiscal_quarter: None,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_company_summary_and_totals() {
+        let mut company = CompanyReport::new("tstc", CompanyStatus::Processed);
+        company.ticker = Some("TSTC".to_string());
+        company.cik = Some("0001234567".to_string());
+        company.filings = vec![
+            FilingReport::from(&result(2023, FilingOutcome::Extracted { paths: vec![PathBuf::from("out/TSTC_2023_Item8.html")], bytes: 10 })),
+            FilingReport::from(&result(2022, FilingOutcome::Failed("Section not found".to_string()))),
+        ];
+        assert_eq!(company.summary(), "TSTC (CIK 0001234567): 1/2 extracted, failed: 2022");
+
+        let missing = CompanyReport::new("ZZZZ", CompanyStatus::NotFound).with_message("no such company");
+        assert_eq!(missing.summary(), "ZZZZ: not found - no such company");
+
+        let totals = RunReport::totals_of(&[company, missing]);
+        assert_eq!((totals.companies, totals.companies_processed, totals.filings, totals.succeeded, totals.failed), (2, 1, 2, 1, 1));
+    }
+}
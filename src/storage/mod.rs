@@ -1,7 +1,9 @@
 // src/storage/mod.rs
 use std::fs;
 use std::path::{Path, PathBuf};
+use crate::edgar::forms::FormMatch;
 use crate::extractors::section::ExtractedSection;
+use crate::pipeline::report::RunReport;
 use crate::utils::error::StorageError;
 use std::io::Write;
 
@@ -23,12 +25,55 @@ impl StorageManager {
         Ok(Self { base_dir: base_path })
     }
 
+    /// Directory holding the EDGAR HTTP response cache
+    pub fn cache_dir(&self) -> PathBuf {
+        self.base_dir.join(".cache").join("edgar")
+    }
+
+    /// Output directory for a section: `/base_dir/TICKER/YEAR/`, or `/base_dir/TICKER/YEAR/Q<n>/`
+    /// for quarterly reports.
+    fn section_dir(&self, section: &ExtractedSection) -> PathBuf {
+        let year_dir = self.base_dir
+            .join(section.ticker.to_uppercase())
+            .join(section.filing_year.to_string());
+        match section.fiscal_quarter {
+            Some(quarter) => year_dir.join(format!("Q{}", quarter)),
+            None => year_dir,
+        }
+    }
+
+    /// Common filename prefix: `TICKER_YEAR` (`TICKER_YEARQ<n>` for quarterly reports), plus
+    /// `_A_<accession>` for amended filings and `_T_<accession>` for transition reports (e.g. a
+    /// 10-KT), so neither overwrites the output of the regular report for the same period.
+    fn file_stem(section: &ExtractedSection) -> String {
+        let mut stem = format!("{}_{}", section.ticker.to_uppercase(), section.filing_year);
+        if let Some(quarter) = section.fiscal_quarter {
+            stem.push_str(&format!("Q{}", quarter));
+        }
+        if section.is_amendment {
+            format!("{}_A_{}", stem, section.accession_number)
+        } else if FormMatch::parse(&section.form_type).is_transition() {
+            format!("{}_T_{}", stem, section.accession_number)
+        } else {
+            stem
+        }
+    }
+
+    /// Section name as used in filenames: "Item 8" -> "Item8", "Part I Item 2" -> "PartI_Item2"
+    fn section_slug(section: &ExtractedSection) -> String {
+        section.section_name
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| pair.concat())
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
     /// Saves the extracted section to a file
     pub fn save_section(&self, section: &ExtractedSection) -> Result<PathBuf, StorageError> {
-        // Create a directory structure like: /base_dir/ticker/year/
-        let target_dir = self.base_dir
-            .join(&section.ticker.to_uppercase())
-            .join(section.filing_year.to_string());
+        // Create a directory structure like: /base_dir/ticker/year/[Q<n>/]
+        let target_dir = self.section_dir(section);
 
         // Create the directories if they don't exist
         if !target_dir.exists() {
@@ -37,9 +82,7 @@ impl StorageManager {
         }
 
         // Create a filename for the section
-        let filename = format!("{}_{}_Item8.html",
-                               section.ticker.to_uppercase(),
-                               section.filing_year);
+        let filename = format!("{}_{}.html", Self::file_stem(section), Self::section_slug(section));
 
         let file_path = target_dir.join(filename);
 
@@ -58,10 +101,8 @@ impl StorageManager {
 
     /// Saves metadata about the section in JSON format
     pub fn save_section_metadata(&self, section: &ExtractedSection) -> Result<PathBuf, StorageError> {
-        // Create a directory structure like: /base_dir/ticker/year/
-        let target_dir = self.base_dir
-            .join(&section.ticker.to_uppercase())
-            .join(section.filing_year.to_string());
+        // Create a directory structure like: /base_dir/ticker/year/[Q<n>/]
+        let target_dir = self.section_dir(section);
 
         // Create the directories if they don't exist
         if !target_dir.exists() {
@@ -70,9 +111,7 @@ impl StorageManager {
         }
 
         // Create a filename for the metadata
-        let filename = format!("{}_{}_Item8_meta.json",
-                              section.ticker.to_uppercase(),
-                              section.filing_year);
+        let filename = format!("{}_{}_meta.json", Self::file_stem(section), Self::section_slug(section));
 
         let file_path = target_dir.join(filename);
 
@@ -81,10 +120,17 @@ impl StorageManager {
             "ticker": section.ticker,
             "company_name": section.company_name,
             "filing_year": section.filing_year,
+            "form_type": section.form_type,
+            "accession_number": section.accession_number,
+            "is_amendment": section.is_amendment,
+            "fiscal_quarter": section.fiscal_quarter,
+            "period_end_date": section.period_end,
             "section_name": section.section_name,
             "section_title": section.section_title,
             // *** Ensure this uses the correct field name ***
             "content_length": section.content_html.len(), // <<< Updated field name
+            "confidence": section.confidence,
+            "quality_flags": section.quality_flags,
             "extraction_timestamp": chrono::Utc::now().to_rfc3339(),
         });
 
@@ -99,4 +145,38 @@ impl StorageManager {
 
         Ok(file_path)
     }
-}
\ No newline at end of file
+
+    /// Saves the aggregated run report as `/base_dir/run_report.json`, replacing the previous run's
+    pub fn save_run_report(&self, report: &RunReport) -> Result<PathBuf, StorageError> {
+        let file_path = self.base_dir.join("run_report.json");
+        let report_str = serde_json::to_string_pretty(report)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        fs::write(&file_path, report_str)
+            .map_err(StorageError::IoError)?;
+        Ok(file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::section::{FilingContext, TEN_K_ITEM_8};
+
+    #[test]
+    fn test_same_year_reports_get_distinct_stems() {
+        let context = |form_type: &str, accession: &str| FilingContext {
+            ticker: "tstc".to_string(),
+            filing_year: 2023,
+            form_type: form_type.to_string(),
+            accession_number: accession.to_string(),
+            is_amendment: form_type.ends_with("/A"),
+            ..FilingContext::default()
+        };
+        let stem = |form_type: &str, accession: &str| {
+            StorageManager::file_stem(&ExtractedSection::new(&TEN_K_ITEM_8, &context(form_type, accession), String::new()))
+        };
+        assert_eq!(stem("10-K", "0001234567-24-000001"), "TSTC_2023");
+        assert_eq!(stem("10-KT", "0001234567-23-000050"), "TSTC_2023_T_0001234567-23-000050");
+        assert_eq!(stem("10-K/A", "0001234567-24-000009"), "TSTC_2023_A_0001234567-24-000009");
+    }
+}
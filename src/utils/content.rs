@@ -0,0 +1,34 @@
+// src/utils/content.rs
+//! Content type detection and text escaping shared by the EDGAR download path and the extractors.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Tags that only occur in HTML. Plain-text filings still carry SGML tags such as
+/// `<PAGE>`, `<TABLE>`, `<CAPTION>`, `<S>` and `<C>`, so those don't count.
+static HTML_MARKER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)<(?:html|body|p|div|br|font|span|h[1-6])[\s/>]").expect("Failed to compile HTML_MARKER_RE")
+});
+
+/// Whether content is an HTML document (as opposed to a plain-text filing).
+pub fn is_html(content: &str) -> bool {
+    HTML_MARKER_RE.is_match(content)
+}
+
+/// Escapes `&`, `<` and `>` in text embedded in HTML, e.g. the `<pre>` block of a plain-text filing.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_detection() {
+        let text = "<PAGE>\nITEM 8. FINANCIAL STATEMENTS\n<TABLE>\n<CAPTION>\n<S>  Total assets  <C> 1,234\n</TABLE>\n";
+        assert!(!is_html(text), "SGML <TABLE>/<PAGE> tags don't make a document HTML");
+        assert!(is_html("<html><body><p>Item 8</p></body></html>"));
+        assert!(is_html("<P>ITEM 8. FINANCIAL STATEMENTS"));
+        assert_eq!(escape_html("<S> R&D <C>"), "&lt;S&gt; R&amp;D &lt;C&gt;");
+    }
+}
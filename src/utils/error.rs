@@ -22,12 +22,33 @@ pub enum EdgarError {
 
     #[error("Failed to parse EDGAR response: {0}")]
     Parse(String),
+
+    #[error("Could not find a company matching '{0}' (expected a ticker, CIK or company name)")]
+    CompanyNotFound(String),
+
+    #[error("'{query}' matches several companies equally well: {candidates}")]
+    AmbiguousCompany {
+        query: String,
+        candidates: String,
+    },
+
+    #[error("Offline mode: no cached response or fixture for {0}")]
+    OfflineMiss(String),
+
+    #[error("EDGAR request failed after {attempts} attempt(s): {source}")]
+    RetriesExhausted {
+        attempts: u32,
+        source: Box<EdgarError>,
+    },
 }
 
 #[derive(Error, Debug)]
 pub enum ExtractError {
     #[error("Regular expression error: {0}")]
     RegexError(String),
+
+    #[error("Invalid section definition: {0}")]
+    InvalidDefinition(String),
     
     #[error("Section not found: {0}")]
     SectionNotFound(String),
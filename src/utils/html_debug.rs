@@ -1,4 +1,5 @@
 // src/utils/html_debug.rs
+#![allow(dead_code)] // Kept for reference until reworked for the DOM approach
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
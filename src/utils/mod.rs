@@ -2,5 +2,6 @@
 pub mod error;
 pub mod logging;
 pub mod html_debug;
+pub mod content;
 
 pub use error::AppError; // Re-export main error type for convenience
\ No newline at end of file